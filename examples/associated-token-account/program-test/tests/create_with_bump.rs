@@ -0,0 +1,188 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::{program_test, program_test_2022},
+    solana_program::{instruction::*, pubkey::Pubkey},
+    solana_program_test::*,
+    solana_sdk::{
+        signature::Signer,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_associated_token_account::{
+        get_associated_token_address_with_program_id,
+        instruction::{create_associated_token_account, create_associated_token_account_with_bump},
+    },
+    spl_token_2022::{extension::ExtensionType, state::Account},
+};
+
+#[tokio::test]
+async fn success_create_with_bump() {
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+    let associated_token_address = get_associated_token_address_with_program_id(
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test_2022(token_mint_address, true).start().await;
+    let rent = banks_client.get_rent().await.unwrap();
+    let expected_token_account_len =
+        ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::ImmutableOwner])
+            .unwrap();
+    let expected_token_account_balance = rent.minimum_balance(expected_token_account_len);
+
+    let instruction = create_associated_token_account_with_bump(
+        &payer.pubkey(),
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let associated_account = banks_client
+        .get_account(associated_token_address)
+        .await
+        .expect("get_account")
+        .expect("associated_account not none");
+    assert_eq!(associated_account.data.len(), expected_token_account_len);
+    assert_eq!(associated_account.owner, spl_token_2022::id());
+    assert_eq!(associated_account.lamports, expected_token_account_balance);
+}
+
+#[tokio::test]
+async fn fail_non_canonical_bump() {
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test_2022(token_mint_address, true).start().await;
+
+    let mut instruction = create_associated_token_account_with_bump(
+        &payer.pubkey(),
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+    // data is [discm, bump]
+    instruction.data[1] = instruction.data[1].wrapping_sub(1); // <-- Non-canonical bump
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    assert_eq!(
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidSeeds)
+    );
+}
+
+/// Compute units consumed creating `wallet_address`'s associated token account
+/// of `token_mint_address` with the instruction built by `build_ix`
+async fn create_compute_units(
+    build_ix: fn(&Pubkey, &Pubkey, &Pubkey, &Pubkey) -> Instruction,
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+) -> u64 {
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test(*token_mint_address, false).start().await;
+    let instruction = build_ix(
+        &payer.pubkey(),
+        wallet_address,
+        token_mint_address,
+        &spl_token::id(),
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    let res = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    res.result.unwrap();
+    res.metadata.unwrap().compute_units_consumed
+}
+
+#[tokio::test]
+async fn create_with_bump_uses_fewer_compute_units_than_create() {
+    // natively-run processors are not metered
+    if std::env::var_os("SBF_OUT_DIR").is_none() && std::env::var_os("BPF_OUT_DIR").is_none() {
+        return;
+    }
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+
+    // same seeds in separate banks so that both find the same bump
+    let create = create_compute_units(
+        create_associated_token_account,
+        &wallet_address,
+        &token_mint_address,
+    )
+    .await;
+    let create_with_bump = create_compute_units(
+        create_associated_token_account_with_bump,
+        &wallet_address,
+        &token_mint_address,
+    )
+    .await;
+    assert!(
+        create_with_bump < create,
+        "CreateWithBump: {create_with_bump} CUs, Create: {create} CUs"
+    );
+}
+
+#[tokio::test]
+async fn fail_non_canonical_bump_logs_stage_and_account() {
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test_2022(token_mint_address, true).start().await;
+
+    let mut instruction = create_associated_token_account_with_bump(
+        &payer.pubkey(),
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+    instruction.data[1] = instruction.data[1].wrapping_sub(1);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    let res = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.result.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidSeeds)
+    );
+    let logs = res.metadata.unwrap().log_messages;
+    assert!(
+        logs.iter().any(|log| log
+            == "Program log: resolve PDA failed for associated_token_account: Provided seeds do not result in a valid address"),
+        "{logs:#?}"
+    );
+}
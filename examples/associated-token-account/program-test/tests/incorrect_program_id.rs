@@ -0,0 +1,55 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    solana_program::{instruction::*, pubkey::Pubkey},
+    solana_program_test::*,
+    solana_sdk::{
+        signature::Signer,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_associated_token_account::{
+        instruction::create_associated_token_account, processor::process_instruction,
+    },
+};
+
+#[tokio::test]
+async fn fail_incorrect_program_id() {
+    let program_id = Pubkey::new_unique();
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+
+    let mut pc = ProgramTest::new(
+        "spl_associated_token_account",
+        program_id,
+        processor!(process_instruction),
+    );
+    pc.add_account_with_file_data(
+        token_mint_address,
+        1461600,
+        spl_token::id(),
+        "token-mint-data.bin",
+    );
+    let (mut banks_client, payer, recent_blockhash) = pc.start().await;
+
+    let mut instruction = create_associated_token_account(
+        &payer.pubkey(),
+        &wallet_address,
+        &token_mint_address,
+        &spl_token::id(),
+    );
+    instruction.program_id = program_id;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    assert_eq!(
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+    );
+}
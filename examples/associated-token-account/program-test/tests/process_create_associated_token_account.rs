@@ -316,3 +316,74 @@ async fn test_create_associated_token_account_using_legacy_implicit_instruction(
     assert_eq!(associated_account.owner, spl_token_2022::id());
     assert_eq!(associated_account.lamports, expected_token_account_balance);
 }
+
+#[tokio::test]
+async fn test_create_associated_token_account_using_legacy_implicit_instruction_wrong_rent() {
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test_2022(token_mint_address, true).start().await;
+
+    let mut create_associated_token_account_ix = create_associated_token_account(
+        &payer.pubkey(),
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+
+    // Legacy implicit instruction, but with the wrong sysvar as the 7th account
+    create_associated_token_account_ix.data = vec![];
+    create_associated_token_account_ix
+        .accounts
+        .push(AccountMeta::new_readonly(sysvar::clock::id(), false)); // <-- Not the rent sysvar
+
+    let mut transaction =
+        Transaction::new_with_payer(&[create_associated_token_account_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    assert_eq!(
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+}
+
+#[tokio::test]
+async fn test_create_associated_token_account_with_extra_account() {
+    let wallet_address = Pubkey::new_unique();
+    let token_mint_address = Pubkey::new_unique();
+    let associated_token_address = get_associated_token_address_with_program_id(
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+
+    let (mut banks_client, payer, recent_blockhash) =
+        program_test_2022(token_mint_address, true).start().await;
+
+    let mut create_associated_token_account_ix = create_associated_token_account(
+        &payer.pubkey(),
+        &wallet_address,
+        &token_mint_address,
+        &spl_token_2022::id(),
+    );
+
+    // Explicit instruction: extra accounts are ignored like upstream
+    create_associated_token_account_ix
+        .accounts
+        .push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+
+    let mut transaction =
+        Transaction::new_with_payer(&[create_associated_token_account_ix], Some(&payer.pubkey()));
+    transaction.sign(&[&payer], recent_blockhash);
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    assert!(banks_client
+        .get_account(associated_token_address)
+        .await
+        .expect("get_account")
+        .is_some());
+}
@@ -5,9 +5,10 @@ mod program_test;
 
 use {
     program_test::{program_test, program_test_2022},
-    solana_program::{pubkey::Pubkey, system_instruction},
+    solana_program::{program_pack::Pack, pubkey::Pubkey, system_instruction},
     solana_program_test::*,
     solana_sdk::{
+        account::Account as SolanaAccount,
         instruction::{AccountMeta, InstructionError},
         signature::Signer,
         signer::keypair::Keypair,
@@ -676,3 +677,49 @@ async fn fail_destination_not_wallet_ata() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn fail_mints_of_different_token_programs_logs_stage_and_account() {
+    let wallet = Keypair::new();
+    let nested_mint = Pubkey::new_unique();
+    let owner_token_account_mint = Pubkey::new_unique();
+    let mut pt = program_test_2022(nested_mint, true);
+    pt.add_account(
+        owner_token_account_mint,
+        SolanaAccount {
+            lamports: 1_000_000_000,
+            data: vec![0; Mint::LEN],
+            owner: spl_token::id(),
+            ..SolanaAccount::default()
+        },
+    );
+    let mut context = pt.start_with_context().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction::recover_nested(
+            &wallet.pubkey(),
+            &owner_token_account_mint,
+            &nested_mint,
+            &spl_token_2022::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &wallet],
+        context.last_blockhash,
+    );
+    let res = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    assert_eq!(
+        res.result.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::IllegalOwner)
+    );
+    let expected_log = format!(
+        "Program log: resolve owner failed for token_program: Provided owner is not allowed: token_program is {}, expected {}",
+        spl_token_2022::id(),
+        spl_token::id(),
+    );
+    let logs = res.metadata.unwrap().log_messages;
+    assert!(logs.contains(&expected_log), "{logs:#?}");
+}
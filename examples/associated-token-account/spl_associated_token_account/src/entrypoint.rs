@@ -1,18 +0,0 @@
-#![cfg(not(feature = "no-entrypoint"))]
-
-use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, program_error::ProgramError,
-    pubkey::Pubkey,
-};
-
-entrypoint!(process_instruction);
-fn process_instruction(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    if *program_id != crate::ID {
-        return Err(ProgramError::IncorrectProgramId);
-    }
-    crate::processor::process_instruction(program_id, accounts, instruction_data)
-}
@@ -1,9 +1,10 @@
 //! fn sigs copied from upstream for program-tests to run without modification
 
+use ideally::ResolveInfallible;
 use solana_program::{instruction::Instruction, pubkey::Pubkey};
 use spl_associated_token_account_interface::{
-    create_idempotent_ix, create_ix, recover_nested_ix, CreateIdempotentIxArgs, CreateIxArgs,
-    RecoverNestedIxArgs,
+    create_idempotent_ix, create_ix, create_with_bump_ix, recover_nested_ix,
+    CreateIdempotentIxArgs, CreateIxArgs, RecoverNestedIxArgs,
 };
 use spl_associated_token_account_lib::resolvers::{
     create::CreateKeysTokenProgramResolved, recover_nested::RecoverNestedRootKeys,
@@ -21,7 +22,7 @@ pub fn create_associated_token_account(
         mint: *token_mint_address,
         token_program: *token_program_id,
     };
-    create_ix(root_keys.resolve().0, CreateIxArgs {}).unwrap()
+    create_ix(root_keys.resolve_infallible().0, CreateIxArgs {}).unwrap()
 }
 
 pub fn create_associated_token_account_idempotent(
@@ -39,6 +40,23 @@ pub fn create_associated_token_account_idempotent(
     create_idempotent_ix(root_keys.resolve_idempotent().0, CreateIdempotentIxArgs {}).unwrap()
 }
 
+/// Not in upstream: `Create` with the bump found off-chain
+pub fn create_associated_token_account_with_bump(
+    funding_address: &Pubkey,
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    let root_keys = CreateKeysTokenProgramResolved {
+        funding_account: *funding_address,
+        wallet: *wallet_address,
+        mint: *token_mint_address,
+        token_program: *token_program_id,
+    };
+    let (keys, args) = root_keys.resolve_with_bump();
+    create_with_bump_ix(keys, args).unwrap()
+}
+
 pub fn recover_nested(
     wallet_address: &Pubkey,
     owner_token_mint_address: &Pubkey,
@@ -51,5 +69,5 @@ pub fn recover_nested(
         nested_mint: *nested_token_mint_address,
         token_program: *token_program_id,
     };
-    recover_nested_ix(root_keys.resolve().0, RecoverNestedIxArgs {}).unwrap()
+    recover_nested_ix(root_keys.resolve_infallible().0, RecoverNestedIxArgs {}).unwrap()
 }
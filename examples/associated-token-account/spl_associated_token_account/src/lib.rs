@@ -1,13 +1,11 @@
 #![forbid(unsafe_code)]
 
-use solana_program::{
-    instruction::{AccountMeta, Instruction},
-    pubkey::Pubkey,
-    sysvar,
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account_lib::{
+    pda::AtaFindPdaArgs,
+    resolvers::{create::CreateKeysTokenProgramResolved, legacy_create::legacy_create_ix},
 };
-use spl_associated_token_account_lib::pda::AtaFindPdaArgs;
 
-mod entrypoint;
 pub mod error;
 pub mod instruction;
 pub mod processor;
@@ -57,6 +55,7 @@ pub fn get_associated_token_address(
 ///   3. `[]` The token mint for the new associated token account
 ///   4. `[]` System program
 ///   5. `[]` SPL Token program
+///   6. `[]` Rent sysvar
 ///
 #[deprecated(
     since = "1.0.5",
@@ -67,20 +66,11 @@ pub fn create_associated_token_account(
     wallet_address: &Pubkey,
     token_mint_address: &Pubkey,
 ) -> Instruction {
-    let associated_account_address =
-        get_associated_token_address(wallet_address, token_mint_address);
-
-    Instruction {
-        program_id: id(),
-        accounts: vec![
-            AccountMeta::new(*funding_address, true),
-            AccountMeta::new(associated_account_address, false),
-            AccountMeta::new_readonly(*wallet_address, false),
-            AccountMeta::new_readonly(*token_mint_address, false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(sysvar::rent::id(), false),
-        ],
-        data: vec![],
-    }
+    let root_keys = CreateKeysTokenProgramResolved {
+        funding_account: *funding_address,
+        wallet: *wallet_address,
+        mint: *token_mint_address,
+        token_program: spl_token::id(),
+    };
+    legacy_create_ix(root_keys.resolve_legacy().0)
 }
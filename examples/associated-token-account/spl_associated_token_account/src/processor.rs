@@ -1,3 +1,4 @@
+use ideally::{accounts_array, verify_with, IdeallyError, ResolveInfallible, ResolveWithProgramId};
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
@@ -10,13 +11,21 @@ use solana_program::{
     sysvar::Sysvar,
 };
 use spl_associated_token_account_interface::{
-    create_verify_account_keys, create_verify_account_privileges,
-    recover_nested_verify_account_keys, recover_nested_verify_account_privileges, CreateAccounts,
-    CreateIxArgs, RecoverNestedAccounts, SplAssociatedTokenAccountError,
-    SplAssociatedTokenAccountProgramIx, CREATE_IX_ACCOUNTS_LEN, RECOVER_NESTED_IX_ACCOUNTS_LEN,
+    CreateAccounts, CreateWithBumpAccounts, CreateWithBumpIxArgs, RecoverNestedAccounts,
+    SplAssociatedTokenAccountError, SplAssociatedTokenAccountProgramIx,
 };
-use spl_associated_token_account_lib::resolvers::{
-    create::CreateRootAccounts, recover_nested::RecoverNestedRootAccounts,
+use spl_associated_token_account_lib::{
+    pda::AtaCreatePdaArgs,
+    resolvers::{
+        create::{CreateRootAccounts, CREATE_KEY_MISMATCH_ERRS},
+        create_with_bump::{
+            to_create_accounts, CreateWithBumpRootAccounts, CREATE_WITH_BUMP_KEY_MISMATCH_ERRS,
+        },
+        legacy_create::{
+            LegacyCreateAccounts, LEGACY_CREATE_IX_ACCOUNTS_LEN, LEGACY_CREATE_KEY_MISMATCH_ERRS,
+        },
+        recover_nested::{RecoverNestedRootAccounts, RECOVER_NESTED_KEY_MISMATCH_ERRS},
+    },
 };
 use spl_token_2022::{
     extension::{ExtensionType, StateWithExtensions},
@@ -34,61 +43,99 @@ enum CreateMode {
     Idempotent,
 }
 
-pub fn process_instruction(
-    _program_id: &Pubkey,
+// Handlers take the program id so that they read like the library's `*_with_program_id` resolvers,
+// but the entrypoint only runs for `crate::ID`
+ideally::program_entrypoint!(
+    crate::ID,
+    SplAssociatedTokenAccountProgramIx,
+    |accounts| {
+        Create(_) => {
+            process_create_associated_token_account(&crate::ID, accounts, CreateMode::Always)
+        },
+        CreateIdempotent(_) => {
+            process_create_associated_token_account(&crate::ID, accounts, CreateMode::Idempotent)
+        },
+        RecoverNested(_) => process_recover_nested(&crate::ID, accounts),
+        CreateWithBump(args) => {
+            process_create_associated_token_account_with_bump(&crate::ID, accounts, args)
+        },
+    },
+    empty => process_create_associated_token_account_legacy(&crate::ID, accounts),
+);
+
+fn process_create_associated_token_account(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    input: &[u8],
+    create_mode: CreateMode,
 ) -> ProgramResult {
-    let instruction = if input.is_empty() {
-        SplAssociatedTokenAccountProgramIx::Create(CreateIxArgs {})
-    } else {
-        SplAssociatedTokenAccountProgramIx::deserialize(&mut &input[..])?
-    };
+    let (expected_keys, ata_create_pda_args) = CreateRootAccounts::from_account_infos(accounts)?
+        .resolve_infallible_with_program_id(program_id);
+    let create_accounts: CreateAccounts = accounts_array(accounts)?.into();
 
-    msg!("{:?}", instruction);
+    verify_with(&create_accounts, &expected_keys, &CREATE_KEY_MISMATCH_ERRS)?;
 
-    match instruction {
-        SplAssociatedTokenAccountProgramIx::Create(_) => {
-            process_create_associated_token_account(accounts, CreateMode::Always)
-        }
-        SplAssociatedTokenAccountProgramIx::CreateIdempotent(_) => {
-            process_create_associated_token_account(accounts, CreateMode::Idempotent)
-        }
-        SplAssociatedTokenAccountProgramIx::RecoverNested(_) => process_recover_nested(accounts),
+    create_associated_token_account(create_accounts, ata_create_pda_args, create_mode)
+}
+
+/// Empty instruction data is `Create`, with the rent sysvar as the 7th account
+/// if there is one, as in the legacy layout
+fn process_create_associated_token_account_legacy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Create (implicit)");
+    if accounts.len() < LEGACY_CREATE_IX_ACCOUNTS_LEN {
+        return process_create_associated_token_account(program_id, accounts, CreateMode::Always);
     }
+    let (expected_keys, ata_create_pda_args) = CreateRootAccounts::from_account_infos(accounts)?
+        .resolve_legacy_with_program_id(program_id);
+    let legacy_accounts: LegacyCreateAccounts = accounts_array(accounts)?.into();
+
+    verify_with(
+        &legacy_accounts,
+        &expected_keys,
+        &LEGACY_CREATE_KEY_MISMATCH_ERRS,
+    )?;
+
+    create_associated_token_account(
+        legacy_accounts.into(),
+        ata_create_pda_args,
+        CreateMode::Always,
+    )
 }
 
-fn process_create_associated_token_account(
+/// Same as `Create`, but with the caller-supplied canonical bump
+/// instead of finding it with `find_program_address`
+fn process_create_associated_token_account_with_bump(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    create_mode: CreateMode,
+    args: CreateWithBumpIxArgs,
 ) -> ProgramResult {
-    let funding_account = accounts.get(0).ok_or(ProgramError::NotEnoughAccountKeys)?;
-    let wallet = accounts.get(2).ok_or(ProgramError::NotEnoughAccountKeys)?;
-    let mint = accounts.get(3).ok_or(ProgramError::NotEnoughAccountKeys)?;
-    let free_accs = CreateRootAccounts {
-        funding_account: *funding_account.key,
-        wallet: *wallet.key,
-        mint,
-    };
-    let (expected_keys, ata_create_pda_args) = free_accs.resolve();
-    let actual_accounts_slice: &[AccountInfo; CREATE_IX_ACCOUNTS_LEN] = accounts
-        .get(..CREATE_IX_ACCOUNTS_LEN)
-        .ok_or(ProgramError::NotEnoughAccountKeys)?
-        .try_into()
-        .unwrap();
-    let create_accounts: CreateAccounts = actual_accounts_slice.into();
+    let (expected_keys, ata_create_pda_args) =
+        CreateWithBumpRootAccounts::from_account_infos(accounts, args)?
+            .resolve_with_program_id(program_id)
+            .map_err(IdeallyError::logged)?;
+    let create_with_bump_accounts: CreateWithBumpAccounts = accounts_array(accounts)?.into();
 
-    if let Err((actual_pubkey, _expected_pubkey)) =
-        create_verify_account_keys(&create_accounts, &expected_keys)
-    {
-        if actual_pubkey == *create_accounts.associated_token_account.key {
-            msg!("Error: Associated address does not match seed derivation");
-            return Err(ProgramError::InvalidSeeds);
-        }
-        return Err(ProgramError::InvalidAccountData);
-    }
-    create_verify_account_privileges(&create_accounts)?;
+    verify_with(
+        &create_with_bump_accounts,
+        &expected_keys,
+        &CREATE_WITH_BUMP_KEY_MISMATCH_ERRS,
+    )?;
+
+    create_associated_token_account(
+        to_create_accounts(create_with_bump_accounts),
+        ata_create_pda_args,
+        CreateMode::Always,
+    )
+}
 
+/// Creates the associated token account after its accounts have been verified
+fn create_associated_token_account(
+    create_accounts: CreateAccounts,
+    ata_create_pda_args: AtaCreatePdaArgs,
+    create_mode: CreateMode,
+) -> ProgramResult {
     if create_mode == CreateMode::Idempotent
         && create_accounts.associated_token_account.owner == create_accounts.token_program.key
     {
@@ -117,15 +164,17 @@ fn process_create_associated_token_account(
         &[ExtensionType::ImmutableOwner],
     )?;
 
-    create_pda_account(
-        create_accounts.funding_account,
-        &rent,
-        account_len,
-        create_accounts.token_program.key,
-        create_accounts.system_program,
-        create_accounts.associated_token_account,
-        &ata_create_pda_args.to_signer_seeds(),
-    )?;
+    ata_create_pda_args.with_signer_seeds(|signer_seeds| {
+        create_pda_account(
+            create_accounts.funding_account,
+            &rent,
+            account_len,
+            create_accounts.token_program.key,
+            create_accounts.system_program,
+            create_accounts.associated_token_account,
+            signer_seeds,
+        )
+    })?;
 
     msg!("Initialize the associated token account");
     invoke(
@@ -154,52 +203,18 @@ fn process_create_associated_token_account(
     )
 }
 
-pub fn process_recover_nested(accounts: &[AccountInfo]) -> ProgramResult {
-    let wallet = accounts.get(5).ok_or(ProgramError::NotEnoughAccountKeys)?;
-    let owner_token_account_mint = accounts.get(4).ok_or(ProgramError::NotEnoughAccountKeys)?;
-    let nested_mint = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
-    let free_accs = RecoverNestedRootAccounts {
-        wallet: *wallet.key,
-        owner_token_account_mint,
-        nested_mint,
-    };
-    let (expected_keys, owner_ata_create_pda_args) = free_accs.resolve()?;
-    let actual_accounts_slice: &[AccountInfo; RECOVER_NESTED_IX_ACCOUNTS_LEN] = accounts
-        .get(..RECOVER_NESTED_IX_ACCOUNTS_LEN)
-        .ok_or(ProgramError::NotEnoughAccountKeys)?
-        .try_into()
-        .unwrap();
-    let recover_nested_accounts: RecoverNestedAccounts = actual_accounts_slice.into();
-
-    if let Err((actual_pubkey, _expected_pubkey)) =
-        recover_nested_verify_account_keys(&recover_nested_accounts, &expected_keys)
-    {
-        // owner address derivation checked
-        if actual_pubkey == *recover_nested_accounts.owner_associated_token_account.key {
-            msg!("Error: Owner associated address does not match seed derivation");
-            return Err(ProgramError::InvalidSeeds);
-        }
-
-        // nested address derivation checked
-        if actual_pubkey == *recover_nested_accounts.nested.key {
-            msg!("Error: Nested associated address does not match seed derivation");
-            return Err(ProgramError::InvalidSeeds);
-        }
-
-        // destination address derivation checked
-        if actual_pubkey == *recover_nested_accounts.wallet_associated_token_account.key {
-            msg!("Error: Destination associated address does not match seed derivation");
-            return Err(ProgramError::InvalidSeeds);
-        }
-
-        if actual_pubkey == *recover_nested_accounts.token_program.key {
-            msg!("Incorrect token program");
-            return Err(ProgramError::IllegalOwner);
-        }
+pub fn process_recover_nested(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let (expected_keys, owner_ata_create_pda_args) =
+        RecoverNestedRootAccounts::from_account_infos(accounts)?
+            .resolve_with_program_id(program_id)
+            .map_err(IdeallyError::logged)?;
+    let recover_nested_accounts: RecoverNestedAccounts = accounts_array(accounts)?.into();
 
-        return Err(ProgramError::InvalidAccountData);
-    }
-    recover_nested_verify_account_privileges(&recover_nested_accounts)?;
+    verify_with(
+        &recover_nested_accounts,
+        &expected_keys,
+        &RECOVER_NESTED_KEY_MISMATCH_ERRS,
+    )?;
 
     // Account data is dropped at the end of this, so the CPI can succeed
     // without a double-borrow
@@ -240,49 +255,51 @@ pub fn process_recover_nested(accounts: &[AccountInfo]) -> ProgramResult {
         (amount, decimals)
     };
 
-    // Transfer everything out
-    invoke_signed(
-        &spl_token_2022::instruction::transfer_checked(
-            recover_nested_accounts.token_program.key,
-            recover_nested_accounts.nested.key,
-            recover_nested_accounts.nested_mint.key,
-            recover_nested_accounts.wallet_associated_token_account.key,
-            recover_nested_accounts.owner_associated_token_account.key,
-            &[],
-            amount,
-            decimals,
-        )?,
-        &[
-            recover_nested_accounts.nested.clone(),
-            recover_nested_accounts.nested_mint.clone(),
-            recover_nested_accounts
-                .wallet_associated_token_account
-                .clone(),
-            recover_nested_accounts
-                .owner_associated_token_account
-                .clone(),
-            recover_nested_accounts.token_program.clone(),
-        ],
-        &[&owner_ata_create_pda_args.to_signer_seeds()],
-    )?;
+    owner_ata_create_pda_args.with_signer_seeds(|signer_seeds| {
+        // Transfer everything out
+        invoke_signed(
+            &spl_token_2022::instruction::transfer_checked(
+                recover_nested_accounts.token_program.key,
+                recover_nested_accounts.nested.key,
+                recover_nested_accounts.nested_mint.key,
+                recover_nested_accounts.wallet_associated_token_account.key,
+                recover_nested_accounts.owner_associated_token_account.key,
+                &[],
+                amount,
+                decimals,
+            )?,
+            &[
+                recover_nested_accounts.nested.clone(),
+                recover_nested_accounts.nested_mint.clone(),
+                recover_nested_accounts
+                    .wallet_associated_token_account
+                    .clone(),
+                recover_nested_accounts
+                    .owner_associated_token_account
+                    .clone(),
+                recover_nested_accounts.token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
 
-    // Close the nested account so it's never used again
-    invoke_signed(
-        &spl_token_2022::instruction::close_account(
-            recover_nested_accounts.token_program.key,
-            recover_nested_accounts.nested.key,
-            recover_nested_accounts.wallet.key,
-            recover_nested_accounts.owner_associated_token_account.key,
-            &[],
-        )?,
-        &[
-            recover_nested_accounts.nested.clone(),
-            recover_nested_accounts.wallet.clone(),
-            recover_nested_accounts
-                .owner_associated_token_account
-                .clone(),
-            recover_nested_accounts.token_program.clone(),
-        ],
-        &[&owner_ata_create_pda_args.to_signer_seeds()],
-    )
+        // Close the nested account so it's never used again
+        invoke_signed(
+            &spl_token_2022::instruction::close_account(
+                recover_nested_accounts.token_program.key,
+                recover_nested_accounts.nested.key,
+                recover_nested_accounts.wallet.key,
+                recover_nested_accounts.owner_associated_token_account.key,
+                &[],
+            )?,
+            &[
+                recover_nested_accounts.nested.clone(),
+                recover_nested_accounts.wallet.clone(),
+                recover_nested_accounts
+                    .owner_associated_token_account
+                    .clone(),
+                recover_nested_accounts.token_program.clone(),
+            ],
+            &[signer_seeds],
+        )
+    })
 }
@@ -12,6 +12,7 @@ pub enum SplAssociatedTokenAccountProgramIx {
     Create(CreateIxArgs),
     CreateIdempotent(CreateIdempotentIxArgs),
     RecoverNested(RecoverNestedIxArgs),
+    CreateWithBump(CreateWithBumpIxArgs),
 }
 impl BorshSerialize for SplAssociatedTokenAccountProgramIx {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
@@ -28,6 +29,10 @@ impl BorshSerialize for SplAssociatedTokenAccountProgramIx {
                 RECOVER_NESTED_IX_DISCM.serialize(writer)?;
                 args.serialize(writer)
             }
+            Self::CreateWithBump(args) => {
+                CREATE_WITH_BUMP_IX_DISCM.serialize(writer)?;
+                args.serialize(writer)
+            }
         }
     }
 }
@@ -42,6 +47,9 @@ impl SplAssociatedTokenAccountProgramIx {
             RECOVER_NESTED_IX_DISCM => {
                 Ok(Self::RecoverNested(RecoverNestedIxArgs::deserialize(buf)?))
             }
+            CREATE_WITH_BUMP_IX_DISCM => Ok(Self::CreateWithBump(
+                CreateWithBumpIxArgs::deserialize(buf)?,
+            )),
             _ => Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
                 format!("discm {:?} not found", maybe_discm),
@@ -649,3 +657,199 @@ pub fn recover_nested_verify_account_privileges(
     }
     Ok(())
 }
+pub const CREATE_WITH_BUMP_IX_ACCOUNTS_LEN: usize = 6;
+#[derive(Copy, Clone, Debug)]
+pub struct CreateWithBumpAccounts<'me, 'info> {
+    ///Funding account (must be a system account)
+    pub funding_account: &'me AccountInfo<'info>,
+    ///Associated token account address to be created
+    pub associated_token_account: &'me AccountInfo<'info>,
+    ///Wallet address for the new associated token account
+    pub wallet: &'me AccountInfo<'info>,
+    ///The token mint for the new associated token account
+    pub mint: &'me AccountInfo<'info>,
+    ///System program
+    pub system_program: &'me AccountInfo<'info>,
+    ///SPL token program
+    pub token_program: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct CreateWithBumpKeys {
+    ///Funding account (must be a system account)
+    pub funding_account: Pubkey,
+    ///Associated token account address to be created
+    pub associated_token_account: Pubkey,
+    ///Wallet address for the new associated token account
+    pub wallet: Pubkey,
+    ///The token mint for the new associated token account
+    pub mint: Pubkey,
+    ///System program
+    pub system_program: Pubkey,
+    ///SPL token program
+    pub token_program: Pubkey,
+}
+impl From<&CreateWithBumpAccounts<'_, '_>> for CreateWithBumpKeys {
+    fn from(accounts: &CreateWithBumpAccounts) -> Self {
+        Self {
+            funding_account: *accounts.funding_account.key,
+            associated_token_account: *accounts.associated_token_account.key,
+            wallet: *accounts.wallet.key,
+            mint: *accounts.mint.key,
+            system_program: *accounts.system_program.key,
+            token_program: *accounts.token_program.key,
+        }
+    }
+}
+impl From<&CreateWithBumpKeys> for [AccountMeta; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN] {
+    fn from(keys: &CreateWithBumpKeys) -> Self {
+        [
+            AccountMeta::new(keys.funding_account, true),
+            AccountMeta::new(keys.associated_token_account, false),
+            AccountMeta::new_readonly(keys.wallet, false),
+            AccountMeta::new_readonly(keys.mint, false),
+            AccountMeta::new_readonly(keys.system_program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+        ]
+    }
+}
+impl From<[Pubkey; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN]> for CreateWithBumpKeys {
+    fn from(pubkeys: [Pubkey; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            funding_account: pubkeys[0],
+            associated_token_account: pubkeys[1],
+            wallet: pubkeys[2],
+            mint: pubkeys[3],
+            system_program: pubkeys[4],
+            token_program: pubkeys[5],
+        }
+    }
+}
+impl<'info> From<&CreateWithBumpAccounts<'_, 'info>>
+    for [AccountInfo<'info>; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: &CreateWithBumpAccounts<'_, 'info>) -> Self {
+        [
+            accounts.funding_account.clone(),
+            accounts.associated_token_account.clone(),
+            accounts.wallet.clone(),
+            accounts.mint.clone(),
+            accounts.system_program.clone(),
+            accounts.token_program.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN]>
+    for CreateWithBumpAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            funding_account: &arr[0],
+            associated_token_account: &arr[1],
+            wallet: &arr[2],
+            mint: &arr[3],
+            system_program: &arr[4],
+            token_program: &arr[5],
+        }
+    }
+}
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateWithBumpIxArgs {
+    pub bump: u8,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreateWithBumpIxData(pub CreateWithBumpIxArgs);
+pub const CREATE_WITH_BUMP_IX_DISCM: u8 = 3u8;
+impl From<CreateWithBumpIxArgs> for CreateWithBumpIxData {
+    fn from(args: CreateWithBumpIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl BorshSerialize for CreateWithBumpIxData {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&[CREATE_WITH_BUMP_IX_DISCM])?;
+        self.0.serialize(writer)
+    }
+}
+impl CreateWithBumpIxData {
+    pub fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let maybe_discm = u8::deserialize(buf)?;
+        if maybe_discm != CREATE_WITH_BUMP_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    CREATE_WITH_BUMP_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(CreateWithBumpIxArgs::deserialize(buf)?))
+    }
+}
+pub fn create_with_bump_ix<K: Into<CreateWithBumpKeys>, A: Into<CreateWithBumpIxArgs>>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: CreateWithBumpKeys = accounts.into();
+    let metas: [AccountMeta; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN] = (&keys).into();
+    let args_full: CreateWithBumpIxArgs = args.into();
+    let data: CreateWithBumpIxData = args_full.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: data.try_to_vec()?,
+    })
+}
+pub fn create_with_bump_invoke<'info, A: Into<CreateWithBumpIxArgs>>(
+    accounts: &CreateWithBumpAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = create_with_bump_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn create_with_bump_invoke_signed<'info, A: Into<CreateWithBumpIxArgs>>(
+    accounts: &CreateWithBumpAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = create_with_bump_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; CREATE_WITH_BUMP_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn create_with_bump_verify_account_keys(
+    accounts: &CreateWithBumpAccounts<'_, '_>,
+    keys: &CreateWithBumpKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.funding_account.key, &keys.funding_account),
+        (
+            accounts.associated_token_account.key,
+            &keys.associated_token_account,
+        ),
+        (accounts.wallet.key, &keys.wallet),
+        (accounts.mint.key, &keys.mint),
+        (accounts.system_program.key, &keys.system_program),
+        (accounts.token_program.key, &keys.token_program),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn create_with_bump_verify_account_privileges(
+    accounts: &CreateWithBumpAccounts<'_, '_>,
+) -> Result<(), ProgramError> {
+    for should_be_writable in [accounts.funding_account, accounts.associated_token_account] {
+        if !should_be_writable.is_writable {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    for should_be_signer in [accounts.funding_account] {
+        if !should_be_signer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }
+    Ok(())
+}
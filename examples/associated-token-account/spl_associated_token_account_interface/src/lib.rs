@@ -3,3 +3,5 @@ pub mod instructions;
 pub use instructions::*;
 pub mod errors;
 pub use errors::*;
+pub mod verify;
+pub use verify::*;
@@ -0,0 +1,100 @@
+//! Not generated by solores: [`ideally`] trait impls for the generated types
+
+use crate::*;
+
+ideally::impl_verify_accounts!(
+    CreateAccounts,
+    CreateKeys,
+    create_verify_account_privileges,
+    CreateAccountField {
+        FundingAccount: funding_account,
+        AssociatedTokenAccount: associated_token_account,
+        Wallet: wallet,
+        Mint: mint,
+        SystemProgram: system_program,
+        TokenProgram: token_program,
+    }
+);
+
+ideally::free_accounts!(
+    CreateAccounts,
+    CREATE_IX_ACCOUNTS_LEN,
+    CreateFreeAccounts {
+        funding_account,
+        wallet,
+        mint,
+    }
+);
+
+ideally::impl_verify_accounts!(
+    CreateIdempotentAccounts,
+    CreateIdempotentKeys,
+    create_idempotent_verify_account_privileges,
+    CreateIdempotentAccountField {
+        FundingAccount: funding_account,
+        AssociatedTokenAccount: associated_token_account,
+        Wallet: wallet,
+        Mint: mint,
+        SystemProgram: system_program,
+        TokenProgram: token_program,
+    }
+);
+
+ideally::free_accounts!(
+    CreateIdempotentAccounts,
+    CREATE_IDEMPOTENT_IX_ACCOUNTS_LEN,
+    CreateIdempotentFreeAccounts {
+        funding_account,
+        wallet,
+        mint,
+    }
+);
+
+ideally::impl_verify_accounts!(
+    RecoverNestedAccounts,
+    RecoverNestedKeys,
+    recover_nested_verify_account_privileges,
+    RecoverNestedAccountField {
+        Nested: nested,
+        NestedMint: nested_mint,
+        WalletAssociatedTokenAccount: wallet_associated_token_account,
+        OwnerAssociatedTokenAccount: owner_associated_token_account,
+        OwnerTokenAccountMint: owner_token_account_mint,
+        Wallet: wallet,
+        TokenProgram: token_program,
+    }
+);
+
+ideally::free_accounts!(
+    RecoverNestedAccounts,
+    RECOVER_NESTED_IX_ACCOUNTS_LEN,
+    RecoverNestedFreeAccounts {
+        nested_mint,
+        owner_token_account_mint,
+        wallet,
+    }
+);
+
+ideally::impl_verify_accounts!(
+    CreateWithBumpAccounts,
+    CreateWithBumpKeys,
+    create_with_bump_verify_account_privileges,
+    CreateWithBumpAccountField {
+        FundingAccount: funding_account,
+        AssociatedTokenAccount: associated_token_account,
+        Wallet: wallet,
+        Mint: mint,
+        SystemProgram: system_program,
+        TokenProgram: token_program,
+    }
+);
+
+ideally::free_accounts!(
+    CreateWithBumpAccounts,
+    CREATE_WITH_BUMP_IX_ACCOUNTS_LEN,
+    CreateWithBumpFreeAccounts {
+        funding_account,
+        wallet,
+        mint,
+    }
+);
@@ -0,0 +1,51 @@
+//! Throughput of batch associated token account derivation.
+//!
+//! Run with `cargo bench --bench find_atas --features client` and compare against
+//! `cargo bench --bench find_atas --features rayon`.
+
+use std::time::{Duration, Instant};
+
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account_lib::pda::{find_atas, AtaCache, AtaFindPdaArgs};
+
+const N: usize = 100_000;
+
+fn report(name: &str, n: usize, elapsed: Duration) {
+    println!(
+        "{name:<24} {n:>8} atas in {elapsed:>10.2?} ({:>10.0} atas/s)",
+        n as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let args: Vec<AtaFindPdaArgs> = (0..N)
+        .map(|i| AtaFindPdaArgs {
+            wallet: Pubkey::new_unique(),
+            token_program: spl_token_2022::ID,
+            mint: mints[i % mints.len()],
+        })
+        .collect();
+
+    let start = Instant::now();
+    let single: Vec<(Pubkey, u8)> = args
+        .iter()
+        .map(|a| a.get_associated_token_address_and_bump_seed())
+        .collect();
+    report("one at a time", N, start.elapsed());
+
+    let start = Instant::now();
+    let batch = find_atas(&args);
+    report("find_atas", N, start.elapsed());
+    assert_eq!(single, batch);
+
+    let mut cache = AtaCache::default();
+    let start = Instant::now();
+    cache.find_atas(&args);
+    report("AtaCache cold", N, start.elapsed());
+
+    let start = Instant::now();
+    let cached = cache.find_atas(&args);
+    report("AtaCache warm", N, start.elapsed());
+    assert_eq!(single, cached);
+}
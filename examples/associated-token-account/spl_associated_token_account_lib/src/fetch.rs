@@ -0,0 +1,378 @@
+//! Off-chain resolution from pubkeys alone: the accounts resolvers need are
+//! fetched through an [`AccountFetcher`] before resolving.
+
+use core::{convert::Infallible, future::Future};
+use std::collections::HashMap;
+
+use ideally::Resolve;
+use solana_program::pubkey::Pubkey;
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountData, ReadonlyAccountOwner};
+use spl_associated_token_account_interface::{CreateKeys, RecoverNestedKeys};
+
+use crate::{
+    pda::AtaCreatePdaArgs,
+    resolvers::{
+        create::CreateRootAccounts,
+        recover_nested::{RecoverNestedError, RecoverNestedRootAccounts},
+    },
+};
+
+mod batch;
+
+pub use batch::*;
+
+/// An account fetched off-chain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchedAccount {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+impl KeyedAccount for FetchedAccount {
+    fn key(&self) -> &Pubkey {
+        &self.pubkey
+    }
+}
+
+impl ReadonlyAccountOwner for FetchedAccount {
+    fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+}
+
+impl ReadonlyAccountData for FetchedAccount {
+    type SliceDeref<'s> = Vec<u8>;
+
+    type DataDeref<'d> = &'d Vec<u8>;
+
+    fn data(&self) -> Self::DataDeref<'_> {
+        &self.data
+    }
+}
+
+/// Fetches accounts for resolvers, e.g. from an RPC or a cache
+pub trait AccountFetcher {
+    type Err;
+
+    /// Fetches `pubkeys` in order, with `None` for accounts that do not exist
+    fn fetch_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> impl Future<Output = Result<Vec<Option<FetchedAccount>>, Self::Err>> + Send;
+}
+
+/// [`AccountFetcher`] over a fixed set of accounts, e.g. for tests
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryFetcher {
+    accounts: HashMap<Pubkey, FetchedAccount>,
+}
+
+impl InMemoryFetcher {
+    /// Returns the account previously at the same pubkey, if any
+    pub fn insert(&mut self, account: FetchedAccount) -> Option<FetchedAccount> {
+        self.accounts.insert(account.pubkey, account)
+    }
+
+    pub fn remove(&mut self, pubkey: &Pubkey) -> Option<FetchedAccount> {
+        self.accounts.remove(pubkey)
+    }
+}
+
+impl FromIterator<FetchedAccount> for InMemoryFetcher {
+    fn from_iter<I: IntoIterator<Item = FetchedAccount>>(iter: I) -> Self {
+        Self {
+            accounts: iter.into_iter().map(|a| (a.pubkey, a)).collect(),
+        }
+    }
+}
+
+impl AccountFetcher for InMemoryFetcher {
+    type Err = Infallible;
+
+    fn fetch_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> impl Future<Output = Result<Vec<Option<FetchedAccount>>, Infallible>> + Send {
+        let res = pubkeys
+            .iter()
+            .map(|pubkey| self.accounts.get(pubkey).cloned())
+            .collect();
+        async move { Ok(res) }
+    }
+}
+
+#[cfg(feature = "rpc")]
+mod rpc {
+    use solana_program::pubkey::Pubkey;
+    use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    use solana_rpc_client_api::client_error::Error as ClientError;
+
+    use super::{AccountFetcher, FetchedAccount};
+
+    /// Max accounts per `getMultipleAccounts` request
+    const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+    /// Fetches with `getMultipleAccounts`, in as many requests as needed
+    impl AccountFetcher for RpcClient {
+        type Err = ClientError;
+
+        async fn fetch_accounts(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<FetchedAccount>>, ClientError> {
+            let mut res = Vec::with_capacity(pubkeys.len());
+            for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+                let accounts = self.get_multiple_accounts(chunk).await?;
+                res.extend(chunk.iter().zip(accounts).map(|(pubkey, account)| {
+                    account.map(|a| FetchedAccount {
+                        pubkey: *pubkey,
+                        owner: a.owner,
+                        data: a.data,
+                    })
+                }));
+            }
+            Ok(res)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FetchResolveError<F, R = Infallible> {
+    Fetch(F),
+
+    /// A required account does not exist
+    AccountNotFound(Pubkey),
+
+    /// [`AccountFetcher::fetch_accounts`] returned fewer accounts than requested
+    ShortResponse {
+        requested: usize,
+        returned: usize,
+    },
+
+    Resolve(R),
+}
+
+async fn fetch_all<F: AccountFetcher, const N: usize, R>(
+    fetcher: &F,
+    pubkeys: [Pubkey; N],
+) -> Result<[FetchedAccount; N], FetchResolveError<F::Err, R>> {
+    let fetched = fetcher
+        .fetch_accounts(&pubkeys)
+        .await
+        .map_err(FetchResolveError::Fetch)?;
+    if fetched.len() < N {
+        return Err(FetchResolveError::ShortResponse {
+            requested: N,
+            returned: fetched.len(),
+        });
+    }
+    let mut res = Vec::with_capacity(N);
+    for (pubkey, account) in pubkeys.into_iter().zip(fetched) {
+        res.push(account.ok_or(FetchResolveError::AccountNotFound(pubkey))?);
+    }
+    // length checked above
+    Ok(res.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+/// [`CreateRootAccounts`] by pubkey
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CreateRootPubkeys {
+    pub funding_account: Pubkey,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+}
+
+impl CreateRootPubkeys {
+    /// Fetches the mint
+    pub async fn fetch<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+    ) -> Result<CreateRootAccounts<FetchedAccount>, FetchResolveError<F::Err>> {
+        let [mint] = fetch_all(fetcher, [self.mint]).await?;
+        Ok(CreateRootAccounts {
+            funding_account: self.funding_account,
+            wallet: self.wallet,
+            mint,
+        })
+    }
+
+    pub async fn resolve<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+    ) -> Result<(CreateKeys, AtaCreatePdaArgs), FetchResolveError<F::Err>> {
+        self.fetch(fetcher)
+            .await?
+            .resolve()
+            .map_err(FetchResolveError::Resolve)
+    }
+}
+
+/// [`RecoverNestedRootAccounts`] by pubkey
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecoverNestedRootPubkeys {
+    pub wallet: Pubkey,
+    pub owner_token_account_mint: Pubkey,
+    pub nested_mint: Pubkey,
+}
+
+impl RecoverNestedRootPubkeys {
+    /// Fetches both mints in a single [`AccountFetcher::fetch_accounts`] call
+    pub async fn fetch<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+    ) -> Result<
+        RecoverNestedRootAccounts<FetchedAccount, FetchedAccount>,
+        FetchResolveError<F::Err, RecoverNestedError>,
+    > {
+        let [owner_token_account_mint, nested_mint] =
+            fetch_all(fetcher, [self.owner_token_account_mint, self.nested_mint]).await?;
+        Ok(RecoverNestedRootAccounts {
+            wallet: self.wallet,
+            owner_token_account_mint,
+            nested_mint,
+        })
+    }
+
+    /// .1 is owner_token_account signer seeds args
+    pub async fn resolve<F: AccountFetcher>(
+        &self,
+        fetcher: &F,
+    ) -> Result<(RecoverNestedKeys, AtaCreatePdaArgs), FetchResolveError<F::Err, RecoverNestedError>>
+    {
+        self.fetch(fetcher)
+            .await?
+            .resolve()
+            .map_err(FetchResolveError::Resolve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_executor::block_on;
+    use ideally::ResolveInfallible;
+    use solana_program::instruction::AccountMeta;
+    use spl_associated_token_account_interface::{
+        CREATE_IX_ACCOUNTS_LEN, RECOVER_NESTED_IX_ACCOUNTS_LEN,
+    };
+
+    use crate::resolvers::{
+        create::CreateKeysTokenProgramResolved, recover_nested::RecoverNestedRootKeys,
+    };
+
+    use super::*;
+
+    fn mint(token_program: Pubkey) -> FetchedAccount {
+        FetchedAccount {
+            pubkey: Pubkey::new_unique(),
+            owner: token_program,
+            data: Vec::new(),
+        }
+    }
+
+    /// Returns no accounts at all
+    struct EmptyFetcher;
+
+    impl AccountFetcher for EmptyFetcher {
+        type Err = Infallible;
+
+        async fn fetch_accounts(
+            &self,
+            _pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<FetchedAccount>>, Infallible> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn create_root_pubkeys_resolves() {
+        let mint = mint(spl_token_2022::ID);
+        let pubkeys = CreateRootPubkeys {
+            funding_account: Pubkey::new_unique(),
+            wallet: Pubkey::new_unique(),
+            mint: mint.pubkey,
+        };
+        let fetcher: InMemoryFetcher = [mint].into_iter().collect();
+        let (keys, args) = block_on(pubkeys.resolve(&fetcher)).unwrap();
+        let (expected_keys, expected_args) = CreateKeysTokenProgramResolved {
+            funding_account: pubkeys.funding_account,
+            wallet: pubkeys.wallet,
+            mint: pubkeys.mint,
+            token_program: spl_token_2022::ID,
+        }
+        .resolve_infallible();
+        assert_eq!(
+            <[AccountMeta; CREATE_IX_ACCOUNTS_LEN]>::from(&keys),
+            <[AccountMeta; CREATE_IX_ACCOUNTS_LEN]>::from(&expected_keys),
+        );
+        assert_eq!(args, expected_args);
+    }
+
+    #[test]
+    fn create_root_pubkeys_missing_mint() {
+        let pubkeys = CreateRootPubkeys {
+            funding_account: Pubkey::new_unique(),
+            wallet: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+        };
+        let err = block_on(pubkeys.resolve(&InMemoryFetcher::default())).unwrap_err();
+        assert_eq!(err, FetchResolveError::AccountNotFound(pubkeys.mint));
+    }
+
+    #[test]
+    fn recover_nested_root_pubkeys_resolves() {
+        let owner_token_account_mint = mint(spl_token::ID);
+        let nested_mint = mint(spl_token::ID);
+        let pubkeys = RecoverNestedRootPubkeys {
+            wallet: Pubkey::new_unique(),
+            owner_token_account_mint: owner_token_account_mint.pubkey,
+            nested_mint: nested_mint.pubkey,
+        };
+        let fetcher: InMemoryFetcher = [owner_token_account_mint, nested_mint]
+            .into_iter()
+            .collect();
+        let (keys, args) = block_on(pubkeys.resolve(&fetcher)).unwrap();
+        let (expected_keys, expected_args) = RecoverNestedRootKeys {
+            wallet: pubkeys.wallet,
+            owner_token_account_mint: pubkeys.owner_token_account_mint,
+            nested_mint: pubkeys.nested_mint,
+            token_program: spl_token::ID,
+        }
+        .resolve_infallible();
+        assert_eq!(
+            <[AccountMeta; RECOVER_NESTED_IX_ACCOUNTS_LEN]>::from(&keys),
+            <[AccountMeta; RECOVER_NESTED_IX_ACCOUNTS_LEN]>::from(&expected_keys),
+        );
+        assert_eq!(args, expected_args);
+    }
+
+    #[test]
+    fn recover_nested_root_pubkeys_missing_mint() {
+        let owner_token_account_mint = mint(spl_token::ID);
+        let pubkeys = RecoverNestedRootPubkeys {
+            wallet: Pubkey::new_unique(),
+            owner_token_account_mint: owner_token_account_mint.pubkey,
+            nested_mint: Pubkey::new_unique(),
+        };
+        let fetcher: InMemoryFetcher = [owner_token_account_mint].into_iter().collect();
+        let err = block_on(pubkeys.resolve(&fetcher)).unwrap_err();
+        assert_eq!(err, FetchResolveError::AccountNotFound(pubkeys.nested_mint));
+    }
+
+    #[test]
+    fn short_response() {
+        let pubkeys = RecoverNestedRootPubkeys {
+            wallet: Pubkey::new_unique(),
+            owner_token_account_mint: Pubkey::new_unique(),
+            nested_mint: Pubkey::new_unique(),
+        };
+        let err = block_on(pubkeys.fetch(&EmptyFetcher)).err();
+        assert_eq!(
+            err,
+            Some(FetchResolveError::ShortResponse {
+                requested: 2,
+                returned: 0,
+            })
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Resolution of many instructions with a single [`AccountFetcher::fetch_accounts`] call,
+//! e.g. creating associated token accounts for a whole airdrop list.
+
+use core::convert::Infallible;
+use std::collections::HashMap;
+
+use ideally::{ResolveInfallible, ResolveWithProgramId};
+use solana_program::pubkey::Pubkey;
+use spl_associated_token_account_interface::{CreateIdempotentKeys, CreateKeys, RecoverNestedKeys};
+
+use crate::{
+    pda::AtaCreatePdaArgs,
+    resolvers::{
+        create::CreateRootAccounts,
+        recover_nested::{RecoverNestedError, RecoverNestedRootAccounts},
+    },
+};
+
+use super::{
+    AccountFetcher, CreateRootPubkeys, FetchResolveError, FetchedAccount, RecoverNestedRootPubkeys,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchRequest {
+    Create(CreateRootPubkeys),
+    CreateIdempotent(CreateRootPubkeys),
+    RecoverNested(RecoverNestedRootPubkeys),
+}
+
+#[derive(Clone, Debug)]
+pub enum BatchResolved {
+    Create(CreateKeys, AtaCreatePdaArgs),
+    CreateIdempotent(CreateIdempotentKeys, AtaCreatePdaArgs),
+
+    /// .1 is owner_token_account signer seeds args
+    RecoverNested(RecoverNestedKeys, AtaCreatePdaArgs),
+}
+
+/// Error resolving a single [`BatchRequest`]
+pub type BatchItemError = FetchResolveError<Infallible, RecoverNestedError>;
+
+impl BatchRequest {
+    /// Accounts this request needs fetched
+    fn mints(&self) -> impl Iterator<Item = Pubkey> {
+        let (a, b) = match self {
+            Self::Create(p) | Self::CreateIdempotent(p) => (p.mint, None),
+            Self::RecoverNested(p) => (p.owner_token_account_mint, Some(p.nested_mint)),
+        };
+        core::iter::once(a).chain(b)
+    }
+
+    fn resolve_fetched(
+        &self,
+        fetched: &HashMap<Pubkey, FetchedAccount>,
+        program_id: &Pubkey,
+    ) -> Result<BatchResolved, BatchItemError> {
+        let get = |pubkey: Pubkey| {
+            fetched
+                .get(&pubkey)
+                .ok_or(FetchResolveError::AccountNotFound(pubkey))
+        };
+        Ok(match self {
+            Self::Create(p) => {
+                let (keys, args) =
+                    create_root(p, get(p.mint)?).resolve_infallible_with_program_id(program_id);
+                BatchResolved::Create(keys, args)
+            }
+            Self::CreateIdempotent(p) => {
+                let (keys, args) =
+                    create_root(p, get(p.mint)?).resolve_idempotent_with_program_id(program_id);
+                BatchResolved::CreateIdempotent(keys, args)
+            }
+            Self::RecoverNested(p) => {
+                let (keys, args) = RecoverNestedRootAccounts {
+                    wallet: p.wallet,
+                    owner_token_account_mint: get(p.owner_token_account_mint)?,
+                    nested_mint: get(p.nested_mint)?,
+                }
+                .resolve_with_program_id(program_id)
+                .map_err(FetchResolveError::Resolve)?;
+                BatchResolved::RecoverNested(keys, args)
+            }
+        })
+    }
+}
+
+fn create_root<'a>(
+    p: &CreateRootPubkeys,
+    mint: &'a FetchedAccount,
+) -> CreateRootAccounts<&'a FetchedAccount> {
+    CreateRootAccounts {
+        funding_account: p.funding_account,
+        wallet: p.wallet,
+        mint,
+    }
+}
+
+/// Resolves every request in `requests`, fetching all the accounts they need,
+/// deduplicated, in a single [`AccountFetcher::fetch_accounts`] call.
+///
+/// Results are in the same order as `requests`. Only failing to fetch, or
+/// [`AccountFetcher::fetch_accounts`] returning fewer accounts than requested,
+/// fails the whole batch.
+pub async fn resolve_batch<F: AccountFetcher>(
+    fetcher: &F,
+    requests: &[BatchRequest],
+) -> Result<Vec<Result<BatchResolved, BatchItemError>>, FetchResolveError<F::Err>> {
+    resolve_batch_with_program_id(
+        fetcher,
+        requests,
+        &spl_associated_token_account_interface::ID,
+    )
+    .await
+}
+
+/// [`resolve_batch`] for a deployment of the program at `program_id`
+pub async fn resolve_batch_with_program_id<F: AccountFetcher>(
+    fetcher: &F,
+    requests: &[BatchRequest],
+    program_id: &Pubkey,
+) -> Result<Vec<Result<BatchResolved, BatchItemError>>, FetchResolveError<F::Err>> {
+    let mut pubkeys: Vec<Pubkey> = requests.iter().flat_map(BatchRequest::mints).collect();
+    pubkeys.sort_unstable();
+    pubkeys.dedup();
+    let accounts = fetcher
+        .fetch_accounts(&pubkeys)
+        .await
+        .map_err(FetchResolveError::Fetch)?;
+    if accounts.len() < pubkeys.len() {
+        return Err(FetchResolveError::ShortResponse {
+            requested: pubkeys.len(),
+            returned: accounts.len(),
+        });
+    }
+    let fetched: HashMap<Pubkey, FetchedAccount> = pubkeys
+        .iter()
+        .copied()
+        .zip(accounts)
+        .filter_map(|(pubkey, account)| account.map(|a| (pubkey, a)))
+        .collect();
+    Ok(requests
+        .iter()
+        .map(|r| r.resolve_fetched(&fetched, program_id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures_executor::block_on;
+    use ideally::ResolveInfallible;
+
+    use crate::{
+        fetch::InMemoryFetcher,
+        resolvers::{
+            create::CreateKeysTokenProgramResolved, recover_nested::RecoverNestedRootKeys,
+        },
+    };
+
+    use super::*;
+
+    /// Records the pubkeys of every [`AccountFetcher::fetch_accounts`] call
+    #[derive(Default)]
+    struct RecordingFetcher {
+        inner: InMemoryFetcher,
+        calls: Mutex<Vec<Vec<Pubkey>>>,
+    }
+
+    impl AccountFetcher for RecordingFetcher {
+        type Err = Infallible;
+
+        async fn fetch_accounts(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<FetchedAccount>>, Infallible> {
+            self.calls.lock().unwrap().push(pubkeys.to_vec());
+            self.inner.fetch_accounts(pubkeys).await
+        }
+    }
+
+    /// Returns one account fewer than requested
+    struct ShortFetcher(InMemoryFetcher);
+
+    impl AccountFetcher for ShortFetcher {
+        type Err = Infallible;
+
+        async fn fetch_accounts(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<FetchedAccount>>, Infallible> {
+            let mut res = self.0.fetch_accounts(pubkeys).await?;
+            res.pop();
+            Ok(res)
+        }
+    }
+
+    fn mint(token_program: Pubkey) -> FetchedAccount {
+        FetchedAccount {
+            pubkey: Pubkey::new_unique(),
+            owner: token_program,
+            data: Vec::new(),
+        }
+    }
+
+    fn create(mint: Pubkey) -> CreateRootPubkeys {
+        CreateRootPubkeys {
+            funding_account: Pubkey::new_unique(),
+            wallet: Pubkey::new_unique(),
+            mint,
+        }
+    }
+
+    fn expected_ata(p: &CreateRootPubkeys, token_program: Pubkey, program_id: &Pubkey) -> Pubkey {
+        CreateKeysTokenProgramResolved {
+            funding_account: p.funding_account,
+            wallet: p.wallet,
+            mint: p.mint,
+            token_program,
+        }
+        .resolve_infallible_with_program_id(program_id)
+        .0
+        .associated_token_account
+    }
+
+    #[test]
+    fn resolves_in_order_with_per_item_errors_and_dedup() {
+        let program_id = Pubkey::new_unique();
+        let token_mint = mint(spl_token::ID);
+        let token_2022_mint = mint(spl_token_2022::ID);
+        let missing_mint = Pubkey::new_unique();
+        let fetcher = RecordingFetcher {
+            inner: [token_mint.clone(), token_2022_mint.clone()]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let create_2022 = create(token_2022_mint.pubkey);
+        let create_idempotent = create(token_mint.pubkey);
+        let create_missing = create(missing_mint);
+        let recover_nested = RecoverNestedRootPubkeys {
+            wallet: Pubkey::new_unique(),
+            owner_token_account_mint: token_mint.pubkey,
+            nested_mint: token_mint.pubkey,
+        };
+        let recover_nested_mismatched = RecoverNestedRootPubkeys {
+            nested_mint: token_2022_mint.pubkey,
+            ..recover_nested
+        };
+        let requests = [
+            BatchRequest::Create(create_2022),
+            BatchRequest::CreateIdempotent(create_idempotent),
+            BatchRequest::Create(create_missing),
+            BatchRequest::RecoverNested(recover_nested),
+            BatchRequest::RecoverNested(recover_nested_mismatched),
+        ];
+
+        let res = block_on(resolve_batch_with_program_id(
+            &fetcher,
+            &requests,
+            &program_id,
+        ))
+        .unwrap();
+        assert_eq!(res.len(), requests.len());
+
+        match &res[0] {
+            Ok(BatchResolved::Create(keys, _)) => assert_eq!(
+                keys.associated_token_account,
+                expected_ata(&create_2022, spl_token_2022::ID, &program_id)
+            ),
+            _ => panic!("expected Create"),
+        }
+        match &res[1] {
+            Ok(BatchResolved::CreateIdempotent(keys, _)) => assert_eq!(
+                keys.associated_token_account,
+                expected_ata(&create_idempotent, spl_token::ID, &program_id)
+            ),
+            _ => panic!("expected CreateIdempotent"),
+        }
+        assert_eq!(
+            res[2].as_ref().err(),
+            Some(&FetchResolveError::AccountNotFound(missing_mint))
+        );
+        match &res[3] {
+            Ok(BatchResolved::RecoverNested(keys, _)) => {
+                let (expected, _) = RecoverNestedRootKeys {
+                    wallet: recover_nested.wallet,
+                    owner_token_account_mint: recover_nested.owner_token_account_mint,
+                    nested_mint: recover_nested.nested_mint,
+                    token_program: spl_token::ID,
+                }
+                .resolve_infallible_with_program_id(&program_id);
+                assert_eq!(keys.nested, expected.nested);
+                assert_eq!(
+                    keys.owner_associated_token_account,
+                    expected.owner_associated_token_account
+                );
+            }
+            _ => panic!("expected RecoverNested"),
+        }
+        assert!(matches!(res[4], Err(FetchResolveError::Resolve(_))));
+
+        let calls = fetcher.calls.into_inner().unwrap();
+        assert_eq!(calls.len(), 1);
+        let mut expected_pubkeys = vec![token_mint.pubkey, token_2022_mint.pubkey, missing_mint];
+        expected_pubkeys.sort_unstable();
+        assert_eq!(calls[0], expected_pubkeys);
+    }
+
+    #[test]
+    fn short_response_fails_batch() {
+        let token_mint = mint(spl_token::ID);
+        let requests = [
+            BatchRequest::Create(create(token_mint.pubkey)),
+            BatchRequest::Create(create(Pubkey::new_unique())),
+        ];
+        let fetcher = ShortFetcher([token_mint].into_iter().collect());
+        let err = block_on(resolve_batch(&fetcher, &requests)).err();
+        assert_eq!(
+            err,
+            Some(FetchResolveError::ShortResponse {
+                requested: 2,
+                returned: 1,
+            })
+        );
+    }
+}
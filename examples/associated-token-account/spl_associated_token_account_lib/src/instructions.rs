@@ -0,0 +1,59 @@
+//! Instruction builders for a deployment of the program at any address.
+//!
+//! The solores-generated `*_ix()` fns always use the canonical
+//! [`spl_associated_token_account_interface::ID`].
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account_interface::{
+    create_idempotent_ix, create_ix, create_with_bump_ix, recover_nested_ix,
+    CreateIdempotentIxArgs, CreateIdempotentKeys, CreateIxArgs, CreateKeys, CreateWithBumpIxArgs,
+    CreateWithBumpKeys, RecoverNestedIxArgs, RecoverNestedKeys,
+};
+
+use crate::resolvers::legacy_create::{legacy_create_ix, LegacyCreateKeys};
+
+pub fn create_ix_with_program_id(
+    program_id: Pubkey,
+    keys: CreateKeys,
+    args: CreateIxArgs,
+) -> std::io::Result<Instruction> {
+    let mut ix = create_ix(keys, args)?;
+    ix.program_id = program_id;
+    Ok(ix)
+}
+
+pub fn create_idempotent_ix_with_program_id(
+    program_id: Pubkey,
+    keys: CreateIdempotentKeys,
+    args: CreateIdempotentIxArgs,
+) -> std::io::Result<Instruction> {
+    let mut ix = create_idempotent_ix(keys, args)?;
+    ix.program_id = program_id;
+    Ok(ix)
+}
+
+pub fn recover_nested_ix_with_program_id(
+    program_id: Pubkey,
+    keys: RecoverNestedKeys,
+    args: RecoverNestedIxArgs,
+) -> std::io::Result<Instruction> {
+    let mut ix = recover_nested_ix(keys, args)?;
+    ix.program_id = program_id;
+    Ok(ix)
+}
+
+pub fn create_with_bump_ix_with_program_id(
+    program_id: Pubkey,
+    keys: CreateWithBumpKeys,
+    args: CreateWithBumpIxArgs,
+) -> std::io::Result<Instruction> {
+    let mut ix = create_with_bump_ix(keys, args)?;
+    ix.program_id = program_id;
+    Ok(ix)
+}
+
+pub fn legacy_create_ix_with_program_id(program_id: Pubkey, keys: LegacyCreateKeys) -> Instruction {
+    let mut ix = legacy_create_ix(keys);
+    ix.program_id = program_id;
+    ix
+}
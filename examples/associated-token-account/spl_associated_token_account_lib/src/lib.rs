@@ -1,2 +1,7 @@
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod instructions;
 pub mod pda;
+#[cfg(feature = "client")]
+pub mod planner;
 pub mod resolvers;
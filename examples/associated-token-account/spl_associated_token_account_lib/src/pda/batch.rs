@@ -0,0 +1,182 @@
+//! Off-chain batch derivation of many associated token account addresses,
+//! e.g. for indexers.
+//!
+//! With the `rayon` feature enabled, derivation runs in parallel on the current
+//! rayon thread pool. Use [`rayon::ThreadPool::install`] to run on a dedicated pool.
+
+use std::collections::HashMap;
+
+use ideally::PdaSeeds;
+use solana_program::pubkey::Pubkey;
+
+use super::AtaFindPdaArgs;
+
+/// Derives the address and bump of every associated token account in `args`, in order
+pub fn find_atas(args: &[AtaFindPdaArgs]) -> Vec<(Pubkey, u8)> {
+    find_atas_with_program_id(args, &spl_associated_token_account_interface::ID)
+}
+
+/// [`find_atas`] for a deployment of the program at `program_id`
+pub fn find_atas_with_program_id(
+    args: &[AtaFindPdaArgs],
+    program_id: &Pubkey,
+) -> Vec<(Pubkey, u8)> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        args.par_iter()
+            .map(|a| a.find_program_address(program_id))
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        args.iter()
+            .map(|a| a.find_program_address(program_id))
+            .collect()
+    }
+}
+
+/// Memoizes derived associated token account addresses by seeds.
+///
+/// The cache is unbounded; [`Self::clear`] it periodically if the set of seeds
+/// seen is unbounded too.
+#[derive(Clone, Debug)]
+pub struct AtaCache {
+    program_id: Pubkey,
+    found: HashMap<AtaFindPdaArgs, (Pubkey, u8)>,
+}
+
+impl Default for AtaCache {
+    fn default() -> Self {
+        Self::with_program_id(spl_associated_token_account_interface::ID)
+    }
+}
+
+impl AtaCache {
+    /// Cache for a deployment of the program at `program_id`
+    pub fn with_program_id(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            found: HashMap::new(),
+        }
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn get(&self, args: &AtaFindPdaArgs) -> Option<(Pubkey, u8)> {
+        self.found.get(args).copied()
+    }
+
+    /// Returns the cached address and bump, deriving and caching it on a miss
+    pub fn find_ata(&mut self, args: AtaFindPdaArgs) -> (Pubkey, u8) {
+        let program_id = self.program_id;
+        *self
+            .found
+            .entry(args)
+            .or_insert_with(|| args.find_program_address(&program_id))
+    }
+
+    /// [`find_atas`] that only derives the seeds not already cached,
+    /// caching them
+    pub fn find_atas(&mut self, args: &[AtaFindPdaArgs]) -> Vec<(Pubkey, u8)> {
+        let mut misses: Vec<AtaFindPdaArgs> = args
+            .iter()
+            .filter(|a| !self.found.contains_key(a))
+            .copied()
+            .collect();
+        misses.sort_unstable_by_key(|a| (a.wallet, a.token_program, a.mint));
+        misses.dedup();
+        let found = find_atas_with_program_id(&misses, &self.program_id);
+        self.found.extend(misses.into_iter().zip(found));
+        args.iter().map(|a| self.found[a]).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.found.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.found.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.found.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(n: usize) -> Vec<AtaFindPdaArgs> {
+        (0..n)
+            .map(|i| AtaFindPdaArgs {
+                wallet: Pubkey::new_unique(),
+                token_program: if i % 2 == 0 {
+                    spl_token::ID
+                } else {
+                    spl_token_2022::ID
+                },
+                mint: Pubkey::new_unique(),
+            })
+            .collect()
+    }
+
+    fn expected(a: &AtaFindPdaArgs, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[a.wallet.as_ref(), a.token_program.as_ref(), a.mint.as_ref()],
+            program_id,
+        )
+    }
+
+    // run with and without the `rayon` feature to cover both paths
+    #[test]
+    fn find_atas_matches_find_program_address() {
+        let args = args(64);
+        let found = find_atas(&args);
+        assert_eq!(found.len(), args.len());
+        for (a, f) in args.iter().zip(found) {
+            assert_eq!(f, expected(a, &spl_associated_token_account_interface::ID));
+        }
+    }
+
+    #[test]
+    fn find_atas_with_program_id_matches_find_program_address() {
+        let program_id = Pubkey::new_unique();
+        let args = args(16);
+        let found = find_atas_with_program_id(&args, &program_id);
+        for (a, f) in args.iter().zip(found) {
+            assert_eq!(f, expected(a, &program_id));
+        }
+    }
+
+    #[test]
+    fn cache_hits_return_same_result() {
+        let program_id = Pubkey::new_unique();
+        let mut cache = AtaCache::with_program_id(program_id);
+        let args = args(8);
+
+        let first = cache.find_ata(args[0]);
+        assert_eq!(first, expected(&args[0], &program_id));
+        assert_eq!(cache.get(&args[0]), Some(first));
+        assert_eq!(cache.find_ata(args[0]), first);
+        assert_eq!(cache.len(), 1);
+
+        // duplicates and already cached seeds in the batch
+        let batch: Vec<_> = args.iter().chain(args.iter()).copied().collect();
+        let found = cache.find_atas(&batch);
+        assert_eq!(cache.len(), args.len());
+        for (a, f) in batch.iter().zip(&found) {
+            assert_eq!(*f, expected(a, &program_id));
+        }
+        assert_eq!(cache.find_atas(&batch), found);
+        assert_eq!(cache.len(), args.len());
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&args[0]), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Associated token accounts for when the mint's token program is not known yet,
+//! e.g. to show addresses before the mint account is fetched.
+
+use ideally::{FindPda, PdaCreateArgs};
+use solana_program::pubkey::Pubkey;
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner};
+
+use super::{AtaCreatePdaArgs, AtaFindPdaArgs, TOKEN_PROGRAMS};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtaCandidate {
+    pub address: Pubkey,
+    pub create_args: AtaCreatePdaArgs,
+}
+
+impl AtaCandidate {
+    pub fn token_program(&self) -> &Pubkey {
+        &self.create_args.find.token_program
+    }
+
+    pub fn bump(&self) -> u8 {
+        self.create_args.bump
+    }
+}
+
+/// The associated token account of a wallet and mint for every token program
+/// in [`TOKEN_PROGRAMS`], in the same order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtaCandidates(pub [AtaCandidate; TOKEN_PROGRAMS.len()]);
+
+impl AtaCandidates {
+    pub fn find(wallet: Pubkey, mint: Pubkey) -> Self {
+        Self::find_with_program_id(wallet, mint, &spl_associated_token_account_interface::ID)
+    }
+
+    /// [`Self::find`] for a deployment of the program at `program_id`
+    pub fn find_with_program_id(wallet: Pubkey, mint: Pubkey, program_id: &Pubkey) -> Self {
+        Self(TOKEN_PROGRAMS.map(|token_program| {
+            let (address, create_args) = AtaFindPdaArgs {
+                wallet,
+                token_program,
+                mint,
+            }
+            .find_pda_with_program_id(program_id);
+            AtaCandidate {
+                address,
+                create_args,
+            }
+        }))
+    }
+
+    /// Returns the candidate of `token_program`, if it is one of [`TOKEN_PROGRAMS`]
+    pub fn get(&self, token_program: &Pubkey) -> Option<&AtaCandidate> {
+        self.0.iter().find(|c| c.token_program() == token_program)
+    }
+
+    /// Picks the candidate of the token program that owns the fetched `mint` account.
+    ///
+    /// Returns `None` if `mint` is not the mint the candidates were found for
+    /// or is not owned by one of [`TOKEN_PROGRAMS`]
+    pub fn pick<M: KeyedAccount + ReadonlyAccountOwner>(&self, mint: &M) -> Option<&AtaCandidate> {
+        let PdaCreateArgs {
+            find: AtaFindPdaArgs { mint: expected, .. },
+            ..
+        } = self.0[0].create_args;
+        if *mint.key() != expected {
+            return None;
+        }
+        self.get(mint.owner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Mint {
+        key: Pubkey,
+        owner: Pubkey,
+    }
+
+    impl KeyedAccount for Mint {
+        fn key(&self) -> &Pubkey {
+            &self.key
+        }
+    }
+
+    impl ReadonlyAccountOwner for Mint {
+        fn owner(&self) -> &Pubkey {
+            &self.owner
+        }
+    }
+
+    #[test]
+    fn pick_by_mint_owner() {
+        let wallet = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let candidates = AtaCandidates::find(wallet, key);
+        for token_program in TOKEN_PROGRAMS {
+            let picked = candidates
+                .pick(&Mint {
+                    key,
+                    owner: token_program,
+                })
+                .unwrap();
+            let (address, create_args) = AtaFindPdaArgs {
+                wallet,
+                token_program,
+                mint: key,
+            }
+            .find_pda();
+            assert_eq!(picked.token_program(), &token_program);
+            assert_eq!(picked.address, address);
+            assert_eq!(picked.create_args, create_args);
+        }
+    }
+
+    #[test]
+    fn pick_foreign_owner_none() {
+        let key = Pubkey::new_unique();
+        let candidates = AtaCandidates::find(Pubkey::new_unique(), key);
+        assert_eq!(
+            candidates.pick(&Mint {
+                key,
+                owner: Pubkey::new_unique(),
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn pick_other_mint_none() {
+        let candidates = AtaCandidates::find(Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(
+            candidates.pick(&Mint {
+                key: Pubkey::new_unique(),
+                owner: spl_token::ID,
+            }),
+            None
+        );
+    }
+}
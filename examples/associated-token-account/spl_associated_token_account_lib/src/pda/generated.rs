@@ -0,0 +1,15 @@
+//! Generated from the IDL's `pdas` by `ideally pdas`. Do not edit.
+
+use ideally::PdaCreateArgs;
+use solana_program::pubkey::Pubkey;
+
+ideally::pda_seeds! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct AtaFindPdaArgs(program_id = spl_associated_token_account_interface::ID) {
+        wallet: Pubkey,
+        token_program: Pubkey,
+        mint: Pubkey,
+    }
+}
+
+pub type AtaCreatePdaArgs = PdaCreateArgs<AtaFindPdaArgs>;
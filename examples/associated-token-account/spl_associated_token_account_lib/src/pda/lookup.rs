@@ -0,0 +1,206 @@
+//! Reverse lookup of associated token account addresses,
+//! e.g. to classify arbitrary token accounts.
+
+use std::collections::{HashMap, HashSet};
+
+use ideally::PdaSeeds;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use spl_token_2022::{extension::StateWithExtensions, state::Account};
+
+use super::{find_atas_with_program_id, AtaFindPdaArgs};
+
+/// The token programs associated token accounts can belong to
+pub const TOKEN_PROGRAMS: [Pubkey; 2] = [spl_token::ID, spl_token_2022::ID];
+
+/// Index of the associated token accounts of every candidate wallet and mint,
+/// for every token program in [`TOKEN_PROGRAMS`].
+///
+/// Every wallet is paired with every mint, so each new candidate wallet
+/// derives `2 * mints` addresses and vice versa.
+#[derive(Clone, Debug)]
+pub struct AtaIndex {
+    program_id: Pubkey,
+    wallets: HashSet<Pubkey>,
+    mints: HashSet<Pubkey>,
+    atas: HashMap<Pubkey, AtaFindPdaArgs>,
+}
+
+impl Default for AtaIndex {
+    fn default() -> Self {
+        Self::with_program_id(spl_associated_token_account_interface::ID)
+    }
+}
+
+impl AtaIndex {
+    /// Index for a deployment of the program at `program_id`
+    pub fn with_program_id(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            wallets: HashSet::new(),
+            mints: HashSet::new(),
+            atas: HashMap::new(),
+        }
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn add_wallet(&mut self, wallet: Pubkey) {
+        if !self.wallets.insert(wallet) {
+            return;
+        }
+        let args: Vec<AtaFindPdaArgs> = self
+            .mints
+            .iter()
+            .flat_map(|mint| {
+                TOKEN_PROGRAMS.map(|token_program| AtaFindPdaArgs {
+                    wallet,
+                    token_program,
+                    mint: *mint,
+                })
+            })
+            .collect();
+        self.insert_all(args);
+    }
+
+    pub fn add_mint(&mut self, mint: Pubkey) {
+        if !self.mints.insert(mint) {
+            return;
+        }
+        let args: Vec<AtaFindPdaArgs> = self
+            .wallets
+            .iter()
+            .flat_map(|wallet| {
+                TOKEN_PROGRAMS.map(|token_program| AtaFindPdaArgs {
+                    wallet: *wallet,
+                    token_program,
+                    mint,
+                })
+            })
+            .collect();
+        self.insert_all(args);
+    }
+
+    /// Adds the owner and mint of a spl-token or Token-2022 token account as candidates
+    pub fn add_token_account(&mut self, data: &[u8]) -> Result<(), ProgramError> {
+        let account = StateWithExtensions::<Account>::unpack(data)?;
+        self.add_wallet(account.base.owner);
+        self.add_mint(account.base.mint);
+        Ok(())
+    }
+
+    fn insert_all(&mut self, args: Vec<AtaFindPdaArgs>) {
+        let found = find_atas_with_program_id(&args, &self.program_id);
+        self.atas
+            .extend(found.into_iter().map(|(ata, _bump)| ata).zip(args));
+    }
+
+    /// Returns the seeds of `address` if it is the associated token account
+    /// of a candidate wallet and mint
+    pub fn lookup(&self, address: &Pubkey) -> Option<&AtaFindPdaArgs> {
+        self.atas.get(address)
+    }
+
+    /// Returns whether `address` is the associated token account of
+    /// `(wallet, mint, token_program)`, regardless of whether they are candidates
+    pub fn is_ata_of(&self, address: &Pubkey, args: &AtaFindPdaArgs) -> bool {
+        match self.atas.get(address) {
+            Some(indexed) => indexed == args,
+            None => args.find_program_address(&self.program_id).0 == *address,
+        }
+    }
+
+    /// Number of indexed associated token accounts
+    pub fn len(&self) -> usize {
+        self.atas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.atas.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_pack::Pack;
+    use spl_token::state::{Account as TokenAccount, AccountState};
+
+    use super::*;
+
+    fn ata(wallet: Pubkey, token_program: Pubkey, mint: Pubkey) -> (Pubkey, AtaFindPdaArgs) {
+        let args = AtaFindPdaArgs {
+            wallet,
+            token_program,
+            mint,
+        };
+        (
+            args.find_program_address(&spl_associated_token_account_interface::ID)
+                .0,
+            args,
+        )
+    }
+
+    #[test]
+    fn lookup_both_token_programs() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut index = AtaIndex::default();
+        index.add_wallet(wallet);
+        assert!(index.is_empty());
+        index.add_mint(mint);
+        assert_eq!(index.len(), TOKEN_PROGRAMS.len());
+
+        for token_program in TOKEN_PROGRAMS {
+            let (address, args) = ata(wallet, token_program, mint);
+            assert_eq!(index.lookup(&address), Some(&args));
+            assert!(index.is_ata_of(&address, &args));
+        }
+    }
+
+    #[test]
+    fn lookup_miss() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut index = AtaIndex::default();
+        index.add_wallet(wallet);
+        index.add_mint(mint);
+
+        assert_eq!(index.lookup(&Pubkey::new_unique()), None);
+        // not a candidate wallet
+        let (address, args) = ata(Pubkey::new_unique(), spl_token::ID, mint);
+        assert_eq!(index.lookup(&address), None);
+        assert!(index.is_ata_of(&address, &args));
+        // indexed, but for other seeds
+        let (address, _) = ata(wallet, spl_token::ID, mint);
+        let (_, token_2022_args) = ata(wallet, spl_token_2022::ID, mint);
+        assert!(!index.is_ata_of(&address, &token_2022_args));
+    }
+
+    #[test]
+    fn add_token_account_data() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut data = [0u8; TokenAccount::LEN];
+        TokenAccount::pack(
+            TokenAccount {
+                mint,
+                owner,
+                amount: 1,
+                state: AccountState::Initialized,
+                ..Default::default()
+            },
+            &mut data,
+        )
+        .unwrap();
+
+        let mut index = AtaIndex::default();
+        index.add_token_account(&data).unwrap();
+        for token_program in TOKEN_PROGRAMS {
+            let (address, args) = ata(owner, token_program, mint);
+            assert_eq!(index.lookup(&address), Some(&args));
+        }
+
+        assert!(index.add_token_account(&data[1..]).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! Off-chain discovery of a wallet's nested associated token accounts, planned into
+//! transactions of `RecoverNested` instructions e.g. for one-click cleanup in wallets.
+
+use std::collections::{HashMap, HashSet};
+
+use ideally::ResolveInfallible;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountData, ReadonlyAccountOwner};
+use spl_associated_token_account_interface::{RecoverNestedKeys, RECOVER_NESTED_IX_ACCOUNTS_LEN};
+
+use crate::resolvers::recover_nested::{token_account_mint_and_owner, RecoverNestedRootKeys};
+
+/// Max serialized transaction size, `solana_sdk::packet::PACKET_DATA_SIZE`
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Finds the nested associated token accounts among `ata_token_accounts`:
+/// token accounts owned by one of `wallet_token_accounts`,
+/// which are `wallet`'s token accounts.
+///
+/// Accounts that are not token accounts are skipped.
+///
+/// Example:
+///
+/// ```rust ignore
+/// let transactions = RecoverNestedPlanner {
+///     wallet,
+///     wallet_token_accounts: &fetched_wallet_token_accounts,
+///     ata_token_accounts: &fetched_token_accounts_owned_by_those,
+/// }
+/// .plan();
+/// ```
+pub struct RecoverNestedPlanner<'a, W, N> {
+    pub wallet: Pubkey,
+    pub wallet_token_accounts: &'a [W],
+    pub ata_token_accounts: &'a [N],
+}
+
+impl<W, N> RecoverNestedPlanner<'_, W, N>
+where
+    W: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData,
+    N: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData,
+{
+    /// Returns the root keys to recover every nested associated token account with,
+    /// in the order of `ata_token_accounts`
+    pub fn find(&self) -> Vec<RecoverNestedRootKeys> {
+        self.find_with_program_id(&spl_associated_token_account_interface::ID)
+    }
+
+    /// [`Self::find`] for a deployment of the program at `program_id`
+    pub fn find_with_program_id(&self, program_id: &Pubkey) -> Vec<RecoverNestedRootKeys> {
+        let owners: HashMap<Pubkey, &W> = self
+            .wallet_token_accounts
+            .iter()
+            .map(|a| (*a.key(), a))
+            .collect();
+        let mut seen = HashSet::new();
+        self.ata_token_accounts
+            .iter()
+            .filter(|nested| seen.insert(*nested.key()))
+            .filter_map(|nested| {
+                let (_, owner) = token_account_mint_and_owner(nested).ok()?;
+                let owner_ata = owners.get(&owner)?;
+                RecoverNestedRootKeys::detect_with_program_id(nested, *owner_ata, program_id)
+                    .ok()
+                    .flatten()
+                    .filter(|keys| keys.wallet == self.wallet)
+            })
+            .collect()
+    }
+
+    /// Resolves every nested associated token account found, grouped into
+    /// transactions of `RecoverNested` instructions that fit
+    /// [`MAX_TRANSACTION_SIZE`] with the wallet as the sole signer and fee payer
+    pub fn plan(&self) -> Vec<Vec<RecoverNestedKeys>> {
+        self.plan_with_program_id(&spl_associated_token_account_interface::ID)
+    }
+
+    /// [`Self::plan`] for a deployment of the program at `program_id`
+    pub fn plan_with_program_id(&self, program_id: &Pubkey) -> Vec<Vec<RecoverNestedKeys>> {
+        let mut res = Vec::new();
+        let mut tx = TransactionSize::new(program_id);
+        let mut ixs = Vec::new();
+        for root in self.find_with_program_id(program_id) {
+            let (keys, _) = root.resolve_infallible_with_program_id(program_id);
+            if !tx.try_add(&keys) {
+                res.push(core::mem::take(&mut ixs));
+                tx = TransactionSize::new(program_id);
+                tx.try_add(&keys);
+            }
+            ixs.push(keys);
+        }
+        if !ixs.is_empty() {
+            res.push(ixs);
+        }
+        res
+    }
+}
+
+/// Tracks the serialized size of a legacy transaction of `RecoverNested` instructions
+/// signed only by the wallet, assuming every compact-u16 length fits in 1 byte
+struct TransactionSize {
+    accounts: HashSet<Pubkey>,
+    n_ixs: usize,
+}
+
+impl TransactionSize {
+    /// Signature count, signature, header, accounts len, blockhash, ixs len
+    const BASE: usize = 1 + 64 + 3 + 1 + 32 + 1;
+
+    /// Program ID index, accounts len, account indices, data len, discriminant
+    const IX: usize = 1 + 1 + RECOVER_NESTED_IX_ACCOUNTS_LEN + 1 + 1;
+
+    fn new(program_id: &Pubkey) -> Self {
+        Self {
+            accounts: HashSet::from([*program_id]),
+            n_ixs: 0,
+        }
+    }
+
+    fn size(&self) -> usize {
+        Self::BASE + 32 * self.accounts.len() + Self::IX * self.n_ixs
+    }
+
+    /// Returns false and leaves self unchanged if the instruction does not fit,
+    /// unless this is the first instruction
+    fn try_add(&mut self, keys: &RecoverNestedKeys) -> bool {
+        let new: HashSet<Pubkey> = <[AccountMeta; RECOVER_NESTED_IX_ACCOUNTS_LEN]>::from(keys)
+            .into_iter()
+            .map(|meta| meta.pubkey)
+            .filter(|k| !self.accounts.contains(k))
+            .collect();
+        let size = self.size() + 32 * new.len() + Self::IX;
+        if self.n_ixs > 0 && size > MAX_TRANSACTION_SIZE {
+            return false;
+        }
+        self.accounts.extend(new);
+        self.n_ixs += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_pack::Pack;
+    use solana_sdk::{packet::PACKET_DATA_SIZE, transaction::Transaction};
+    use spl_associated_token_account_interface::{recover_nested_ix, RecoverNestedIxArgs};
+    use spl_token::state::{Account, AccountState};
+
+    use crate::pda::AtaFindPdaArgs;
+
+    use super::*;
+
+    struct TokenAccount {
+        pubkey: Pubkey,
+        token_program: Pubkey,
+        data: Vec<u8>,
+    }
+
+    impl TokenAccount {
+        fn new(pubkey: Pubkey, token_program: Pubkey, mint: Pubkey, owner: Pubkey) -> Self {
+            let mut data = vec![0; Account::LEN];
+            Account {
+                mint,
+                owner,
+                state: AccountState::Initialized,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut data);
+            Self {
+                pubkey,
+                token_program,
+                data,
+            }
+        }
+
+        /// The associated token account of `wallet` for `mint`
+        fn ata(wallet: Pubkey, token_program: Pubkey, mint: Pubkey) -> Self {
+            let (pubkey, _) = AtaFindPdaArgs {
+                wallet,
+                token_program,
+                mint,
+            }
+            .get_associated_token_address_and_bump_seed();
+            Self::new(pubkey, token_program, mint, wallet)
+        }
+    }
+
+    impl KeyedAccount for TokenAccount {
+        fn key(&self) -> &Pubkey {
+            &self.pubkey
+        }
+    }
+
+    impl ReadonlyAccountOwner for TokenAccount {
+        fn owner(&self) -> &Pubkey {
+            &self.token_program
+        }
+    }
+
+    impl ReadonlyAccountData for TokenAccount {
+        type SliceDeref<'s> = Vec<u8>;
+
+        type DataDeref<'d> = &'d Vec<u8>;
+
+        fn data(&self) -> Self::DataDeref<'_> {
+            &self.data
+        }
+    }
+
+    #[test]
+    fn max_transaction_size_is_packet_data_size() {
+        assert_eq!(MAX_TRANSACTION_SIZE, PACKET_DATA_SIZE);
+    }
+
+    #[test]
+    fn find_nested_only() {
+        let wallet = Pubkey::new_unique();
+        let owner_mint = Pubkey::new_unique();
+        let owner_ata = TokenAccount::ata(wallet, spl_token::ID, owner_mint);
+        let foreign_owner_ata = TokenAccount::ata(Pubkey::new_unique(), spl_token::ID, owner_mint);
+
+        let nested_mint = Pubkey::new_unique();
+        let nested = TokenAccount::ata(owner_ata.pubkey, spl_token::ID, nested_mint);
+        // owned by the owner ATA, but not at its associated address
+        let non_nested = TokenAccount::new(
+            Pubkey::new_unique(),
+            spl_token::ID,
+            Pubkey::new_unique(),
+            owner_ata.pubkey,
+        );
+        let foreign_nested =
+            TokenAccount::ata(foreign_owner_ata.pubkey, spl_token::ID, nested_mint);
+        let nested_dup = TokenAccount::ata(owner_ata.pubkey, spl_token::ID, nested_mint);
+
+        let found = RecoverNestedPlanner {
+            wallet,
+            wallet_token_accounts: &[owner_ata, foreign_owner_ata],
+            ata_token_accounts: &[non_nested, nested, foreign_nested, nested_dup],
+        }
+        .find();
+        assert_eq!(found.len(), 1);
+        let RecoverNestedRootKeys {
+            wallet: found_wallet,
+            owner_token_account_mint,
+            nested_mint: found_nested_mint,
+            token_program,
+        } = found[0];
+        assert_eq!(found_wallet, wallet);
+        assert_eq!(owner_token_account_mint, owner_mint);
+        assert_eq!(found_nested_mint, nested_mint);
+        assert_eq!(token_program, spl_token::ID);
+    }
+
+    #[test]
+    fn planned_transactions_fit() {
+        const N_NESTED: usize = 30;
+
+        let wallet = Pubkey::new_unique();
+        let owner_atas: Vec<TokenAccount> = (0..3)
+            .map(|_| TokenAccount::ata(wallet, spl_token::ID, Pubkey::new_unique()))
+            .collect();
+        let nested: Vec<TokenAccount> = (0..N_NESTED)
+            .map(|i| {
+                TokenAccount::ata(
+                    owner_atas[i % owner_atas.len()].pubkey,
+                    spl_token::ID,
+                    Pubkey::new_unique(),
+                )
+            })
+            .collect();
+
+        let plan = RecoverNestedPlanner {
+            wallet,
+            wallet_token_accounts: &owner_atas,
+            ata_token_accounts: &nested,
+        }
+        .plan();
+        assert!(plan.len() > 1);
+        assert_eq!(plan.iter().map(Vec::len).sum::<usize>(), N_NESTED);
+
+        let tx_size = |keys: &[RecoverNestedKeys]| {
+            let ixs: Vec<_> = keys
+                .iter()
+                .map(|k| recover_nested_ix(*k, RecoverNestedIxArgs {}).unwrap())
+                .collect();
+            let tx = Transaction::new_with_payer(&ixs, Some(&wallet));
+            assert_eq!(tx.message.header.num_required_signatures, 1);
+            bincode::serialized_size(&tx).unwrap() as usize
+        };
+        for (i, ixs) in plan.iter().enumerate() {
+            assert!(tx_size(ixs) <= PACKET_DATA_SIZE);
+            // each transaction is as full as possible
+            if let Some(next) = plan.get(i + 1) {
+                let mut overfull = ixs.clone();
+                overfull.push(next[0]);
+                assert!(tx_size(&overfull) > PACKET_DATA_SIZE);
+            }
+        }
+    }
+}
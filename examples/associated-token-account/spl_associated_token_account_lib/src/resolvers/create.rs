@@ -1,15 +1,47 @@
-use solana_program::{pubkey::Pubkey, system_program};
+use ideally::{KeyMismatchErrs, KeyMismatchRule, ResolveInfallible, Resolver};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, system_program,
+};
 use solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner};
-use spl_associated_token_account_interface::{CreateIdempotentKeys, CreateKeys};
+use spl_associated_token_account_interface::{
+    CreateAccountField, CreateFreeAccounts, CreateIdempotentKeys, CreateKeys,
+};
 
 use crate::pda::{AtaCreatePdaArgs, AtaFindPdaArgs};
 
+/// Errors returned by the upstream program for mismatched `Create` and
+/// `CreateIdempotent` accounts
+pub const CREATE_KEY_MISMATCH_ERRS: KeyMismatchErrs<CreateAccountField> = KeyMismatchErrs {
+    rules: &[KeyMismatchRule {
+        field: CreateAccountField::AssociatedTokenAccount,
+        err: ProgramError::InvalidSeeds,
+        msg: Some("Error: Associated address does not match seed derivation"),
+    }],
+    default: ProgramError::InvalidAccountData,
+};
+
+#[derive(Resolver)]
+#[resolver(keys = CreateKeys)]
+#[resolver(owner(token_program = mint))]
+#[resolver(constant(system_program = system_program::ID))]
+#[resolver(pda(
+    associated_token_account = AtaFindPdaArgs { wallet, token_program, mint },
+    signer
+))]
 pub struct CreateRootAccounts<M: KeyedAccount + ReadonlyAccountOwner> {
     pub funding_account: Pubkey,
     pub wallet: Pubkey,
+    #[resolver(account)]
     pub mint: M,
 }
 
+#[derive(Resolver)]
+#[resolver(keys = CreateKeys)]
+#[resolver(constant(system_program = system_program::ID))]
+#[resolver(pda(
+    associated_token_account = AtaFindPdaArgs { wallet, token_program, mint },
+    signer
+))]
 pub struct CreateKeysTokenProgramResolved {
     pub funding_account: Pubkey,
     pub wallet: Pubkey,
@@ -17,8 +49,27 @@ pub struct CreateKeysTokenProgramResolved {
     pub token_program: Pubkey,
 }
 
+impl<'me, 'info> CreateRootAccounts<&'me AccountInfo<'info>> {
+    /// Extracts the free accounts from the `Create` or `CreateIdempotent`
+    /// instruction's accounts.
+    ///
+    /// Returns [`ProgramError::NotEnoughAccountKeys`] if there are not enough accounts
+    pub fn from_account_infos(accounts: &'me [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let CreateFreeAccounts {
+            funding_account,
+            wallet,
+            mint,
+        } = CreateFreeAccounts::from_account_infos(accounts)?;
+        Ok(Self {
+            funding_account: *funding_account.key,
+            wallet: *wallet.key,
+            mint,
+        })
+    }
+}
+
 impl<M: KeyedAccount + ReadonlyAccountOwner> CreateRootAccounts<M> {
-    fn resolve_token_program(&self) -> CreateKeysTokenProgramResolved {
+    pub fn resolve_token_program(&self) -> CreateKeysTokenProgramResolved {
         let mint = *self.mint.key();
         let token_program = *self.mint.owner();
         CreateKeysTokenProgramResolved {
@@ -29,63 +80,120 @@ impl<M: KeyedAccount + ReadonlyAccountOwner> CreateRootAccounts<M> {
         }
     }
 
-    pub fn resolve(&self) -> (CreateKeys, AtaCreatePdaArgs) {
-        self.resolve_token_program().resolve()
-    }
-
     /// plz figure out they're the same type and optimize this away compiler
     pub fn resolve_idempotent(&self) -> (CreateIdempotentKeys, AtaCreatePdaArgs) {
         self.resolve_token_program().resolve_idempotent()
     }
+
+    pub fn resolve_idempotent_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> (CreateIdempotentKeys, AtaCreatePdaArgs) {
+        self.resolve_token_program()
+            .resolve_idempotent_with_program_id(program_id)
+    }
 }
 
 impl CreateKeysTokenProgramResolved {
-    pub fn resolve(&self) -> (CreateKeys, AtaCreatePdaArgs) {
-        let find_pda_args = AtaFindPdaArgs {
-            wallet: self.wallet,
-            mint: self.mint,
-            token_program: self.token_program,
+    /// plz figure out they're the same type and optimize this away compiler
+    pub fn resolve_idempotent(&self) -> (CreateIdempotentKeys, AtaCreatePdaArgs) {
+        to_idempotent(self.resolve_infallible())
+    }
+
+    pub fn resolve_idempotent_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> (CreateIdempotentKeys, AtaCreatePdaArgs) {
+        to_idempotent(self.resolve_infallible_with_program_id(program_id))
+    }
+}
+
+fn to_idempotent(
+    (
+        CreateKeys {
+            funding_account,
+            wallet,
+            mint,
+            token_program,
+            system_program,
+            associated_token_account,
+        },
+        create_pda_args,
+    ): (CreateKeys, AtaCreatePdaArgs),
+) -> (CreateIdempotentKeys, AtaCreatePdaArgs) {
+    (
+        CreateIdempotentKeys {
+            funding_account,
+            wallet,
+            mint,
+            token_program,
+            system_program,
+            associated_token_account,
+        },
+        create_pda_args,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ideally::{OverridesError, Resolve, WithOverrides};
+
+    use super::*;
+
+    /// A mint whose account was not fetched
+    struct MintKey(Pubkey);
+
+    impl KeyedAccount for MintKey {
+        fn key(&self) -> &Pubkey {
+            &self.0
+        }
+    }
+
+    impl ReadonlyAccountOwner for MintKey {
+        fn owner(&self) -> &Pubkey {
+            panic!("mint owner read")
+        }
+    }
+
+    #[test]
+    fn pinned_token_program_does_not_need_mint_account() {
+        let resolved = CreateKeysTokenProgramResolved {
+            funding_account: Pubkey::new_unique(),
+            wallet: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            token_program: spl_token_2022::ID,
         };
-        let (ata, bump) = find_pda_args.get_associated_token_address_and_bump_seed();
-        (
-            CreateKeys {
-                funding_account: self.funding_account,
-                wallet: self.wallet,
-                mint: self.mint,
-                token_program: self.token_program,
-                system_program: system_program::ID,
-                associated_token_account: ata,
+        let (keys, args) = WithOverrides {
+            resolver: CreateRootAccounts {
+                funding_account: resolved.funding_account,
+                wallet: resolved.wallet,
+                mint: MintKey(resolved.mint),
             },
-            AtaCreatePdaArgs {
-                find: find_pda_args,
-                bump: [bump],
-            },
-        )
+            overrides: &[(CreateAccountField::TokenProgram, spl_token_2022::ID)],
+        }
+        .resolve()
+        .unwrap();
+        let (expected_keys, expected_args) = resolved.resolve_infallible();
+        assert_eq!(
+            keys.associated_token_account,
+            expected_keys.associated_token_account
+        );
+        assert_eq!(keys.token_program, spl_token_2022::ID);
+        assert_eq!(args, expected_args);
     }
 
-    /// plz figure out they're the same type and optimize this away compiler
-    pub fn resolve_idempotent(&self) -> (CreateIdempotentKeys, AtaCreatePdaArgs) {
-        let (
-            CreateKeys {
-                funding_account,
-                wallet,
-                mint,
-                token_program,
-                system_program,
-                associated_token_account,
+    #[test]
+    fn pinned_free_account_rejected() {
+        let err = WithOverrides {
+            resolver: CreateRootAccounts {
+                funding_account: Pubkey::new_unique(),
+                wallet: Pubkey::new_unique(),
+                mint: MintKey(Pubkey::new_unique()),
             },
-            create_pda_args,
-        ) = self.resolve();
-        (
-            CreateIdempotentKeys {
-                funding_account,
-                wallet,
-                mint,
-                token_program,
-                system_program,
-                associated_token_account,
-            },
-            create_pda_args,
-        )
+            overrides: &[(CreateAccountField::Wallet, Pubkey::new_unique())],
+        }
+        .resolve()
+        .unwrap_err();
+        assert_eq!(err, OverridesError::FreeAccount(CreateAccountField::Wallet));
     }
 }
@@ -0,0 +1,183 @@
+use ideally::{
+    IdeallyError, KeyMismatchErrs, KeyMismatchRule, PdaCreateArgs, PdaSeeds, Resolve,
+    ResolveInfallible, ResolveWithProgramId, Stage,
+};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, system_program,
+};
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner};
+use spl_associated_token_account_interface::{
+    CreateAccounts, CreateKeys, CreateWithBumpAccountField, CreateWithBumpAccounts,
+    CreateWithBumpFreeAccounts, CreateWithBumpIxArgs, CreateWithBumpKeys,
+    SplAssociatedTokenAccountError,
+};
+
+use crate::pda::{AtaCreatePdaArgs, AtaFindPdaArgs};
+
+use super::create::{CreateKeysTokenProgramResolved, CreateRootAccounts};
+
+pub type CreateWithBumpError =
+    IdeallyError<CreateWithBumpAccountField, SplAssociatedTokenAccountError>;
+
+/// Errors returned for mismatched `CreateWithBump` accounts, same as `Create`'s
+pub const CREATE_WITH_BUMP_KEY_MISMATCH_ERRS: KeyMismatchErrs<CreateWithBumpAccountField> =
+    KeyMismatchErrs {
+        rules: &[KeyMismatchRule {
+            field: CreateWithBumpAccountField::AssociatedTokenAccount,
+            err: ProgramError::InvalidSeeds,
+            msg: Some("Error: Associated address does not match seed derivation"),
+        }],
+        default: ProgramError::InvalidAccountData,
+    };
+
+/// On-chain resolver for `CreateWithBump`: same as `Create`, but
+/// the associated token account is created from the canonical bump supplied
+/// in instruction data instead of being found.
+///
+/// Verify the resolved [`CreateWithBumpKeys`] against [`CreateWithBumpAccounts`]
+/// with [`CREATE_WITH_BUMP_KEY_MISMATCH_ERRS`]
+pub struct CreateWithBumpRootAccounts<M: KeyedAccount + ReadonlyAccountOwner> {
+    pub root: CreateRootAccounts<M>,
+    pub bump: u8,
+}
+
+impl<'me, 'info> CreateWithBumpRootAccounts<&'me AccountInfo<'info>> {
+    /// Extracts the free accounts from the `CreateWithBump` instruction's accounts.
+    ///
+    /// Returns [`ProgramError::NotEnoughAccountKeys`] if there are not enough accounts
+    pub fn from_account_infos(
+        accounts: &'me [AccountInfo<'info>],
+        CreateWithBumpIxArgs { bump }: CreateWithBumpIxArgs,
+    ) -> Result<Self, ProgramError> {
+        let CreateWithBumpFreeAccounts {
+            funding_account,
+            wallet,
+            mint,
+        } = CreateWithBumpFreeAccounts::from_account_infos(accounts)?;
+        Ok(Self {
+            root: CreateRootAccounts {
+                funding_account: *funding_account.key,
+                wallet: *wallet.key,
+                mint,
+            },
+            bump,
+        })
+    }
+}
+
+impl<M: KeyedAccount + ReadonlyAccountOwner> Resolve for CreateWithBumpRootAccounts<M> {
+    type Keys = CreateWithBumpKeys;
+    type Data = AtaCreatePdaArgs;
+    type Err = CreateWithBumpError;
+
+    fn resolve(&self) -> Result<(CreateWithBumpKeys, AtaCreatePdaArgs), CreateWithBumpError> {
+        self.resolve_with_program_id(&spl_associated_token_account_interface::ID)
+    }
+}
+
+impl<M: KeyedAccount + ReadonlyAccountOwner> ResolveWithProgramId
+    for CreateWithBumpRootAccounts<M>
+{
+    /// Returns [`ProgramError::InvalidSeeds`] if the bump is invalid or not canonical
+    fn resolve_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<(CreateWithBumpKeys, AtaCreatePdaArgs), CreateWithBumpError> {
+        let CreateKeysTokenProgramResolved {
+            funding_account,
+            wallet,
+            mint,
+            token_program,
+        } = self.root.resolve_token_program();
+        let find = AtaFindPdaArgs {
+            wallet,
+            token_program,
+            mint,
+        };
+        let associated_token_account = find
+            .create_canonical_program_address(self.bump, program_id)
+            .map_err(|_| {
+                IdeallyError::program(
+                    Stage::ResolvePda,
+                    Some(CreateWithBumpAccountField::AssociatedTokenAccount),
+                    ProgramError::InvalidSeeds,
+                )
+            })?;
+        Ok((
+            CreateWithBumpKeys {
+                funding_account,
+                associated_token_account,
+                wallet,
+                mint,
+                system_program: system_program::ID,
+                token_program,
+            },
+            PdaCreateArgs {
+                find,
+                bump: self.bump,
+            },
+        ))
+    }
+}
+
+/// `CreateWithBump` has the same accounts as `Create`
+pub fn to_create_accounts<'me, 'info>(
+    CreateWithBumpAccounts {
+        funding_account,
+        associated_token_account,
+        wallet,
+        mint,
+        system_program,
+        token_program,
+    }: CreateWithBumpAccounts<'me, 'info>,
+) -> CreateAccounts<'me, 'info> {
+    CreateAccounts {
+        funding_account,
+        associated_token_account,
+        wallet,
+        mint,
+        system_program,
+        token_program,
+    }
+}
+
+impl CreateKeysTokenProgramResolved {
+    /// Finds the associated token account and its bump off-chain
+    /// so that the program does not have to
+    pub fn resolve_with_bump(&self) -> (CreateWithBumpKeys, CreateWithBumpIxArgs) {
+        to_with_bump(self.resolve_infallible())
+    }
+
+    pub fn resolve_with_bump_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> (CreateWithBumpKeys, CreateWithBumpIxArgs) {
+        to_with_bump(self.resolve_infallible_with_program_id(program_id))
+    }
+}
+
+fn to_with_bump(
+    (
+        CreateKeys {
+            funding_account,
+            associated_token_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+        },
+        PdaCreateArgs { bump, .. },
+    ): (CreateKeys, AtaCreatePdaArgs),
+) -> (CreateWithBumpKeys, CreateWithBumpIxArgs) {
+    (
+        CreateWithBumpKeys {
+            funding_account,
+            associated_token_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+        },
+        CreateWithBumpIxArgs { bump },
+    )
+}
@@ -0,0 +1,239 @@
+//! The legacy `Create` layout, with no instruction data and the rent sysvar as a 7th account,
+//! that the deprecated upstream `create_associated_token_account()` still builds.
+//!
+//! Not in the IDL, so the solores-style types are handwritten here.
+
+use ideally::{KeyMismatchErrs, KeyMismatchRule, ResolveInfallible};
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar,
+};
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner};
+use spl_associated_token_account_interface::{CreateAccounts, CreateKeys};
+
+use crate::pda::AtaCreatePdaArgs;
+
+use super::create::{CreateKeysTokenProgramResolved, CreateRootAccounts};
+
+pub const LEGACY_CREATE_IX_ACCOUNTS_LEN: usize = 7;
+
+#[derive(Copy, Clone, Debug)]
+pub struct LegacyCreateAccounts<'me, 'info> {
+    ///Funding account (must be a system account)
+    pub funding_account: &'me AccountInfo<'info>,
+    ///Associated token account address to be created
+    pub associated_token_account: &'me AccountInfo<'info>,
+    ///Wallet address for the new associated token account
+    pub wallet: &'me AccountInfo<'info>,
+    ///The token mint for the new associated token account
+    pub mint: &'me AccountInfo<'info>,
+    ///System program
+    pub system_program: &'me AccountInfo<'info>,
+    ///SPL Token program
+    pub token_program: &'me AccountInfo<'info>,
+    ///Rent sysvar
+    pub rent: &'me AccountInfo<'info>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct LegacyCreateKeys {
+    ///Funding account (must be a system account)
+    pub funding_account: Pubkey,
+    ///Associated token account address to be created
+    pub associated_token_account: Pubkey,
+    ///Wallet address for the new associated token account
+    pub wallet: Pubkey,
+    ///The token mint for the new associated token account
+    pub mint: Pubkey,
+    ///System program
+    pub system_program: Pubkey,
+    ///SPL Token program
+    pub token_program: Pubkey,
+    ///Rent sysvar
+    pub rent: Pubkey,
+}
+
+impl From<&LegacyCreateAccounts<'_, '_>> for LegacyCreateKeys {
+    fn from(accounts: &LegacyCreateAccounts) -> Self {
+        Self {
+            funding_account: *accounts.funding_account.key,
+            associated_token_account: *accounts.associated_token_account.key,
+            wallet: *accounts.wallet.key,
+            mint: *accounts.mint.key,
+            system_program: *accounts.system_program.key,
+            token_program: *accounts.token_program.key,
+            rent: *accounts.rent.key,
+        }
+    }
+}
+
+impl From<&LegacyCreateKeys> for [AccountMeta; LEGACY_CREATE_IX_ACCOUNTS_LEN] {
+    fn from(keys: &LegacyCreateKeys) -> Self {
+        [
+            AccountMeta::new(keys.funding_account, true),
+            AccountMeta::new(keys.associated_token_account, false),
+            AccountMeta::new_readonly(keys.wallet, false),
+            AccountMeta::new_readonly(keys.mint, false),
+            AccountMeta::new_readonly(keys.system_program, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.rent, false),
+        ]
+    }
+}
+
+impl<'me, 'info> From<&'me [AccountInfo<'info>; LEGACY_CREATE_IX_ACCOUNTS_LEN]>
+    for LegacyCreateAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; LEGACY_CREATE_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            funding_account: &arr[0],
+            associated_token_account: &arr[1],
+            wallet: &arr[2],
+            mint: &arr[3],
+            system_program: &arr[4],
+            token_program: &arr[5],
+            rent: &arr[6],
+        }
+    }
+}
+
+/// Drops the rent sysvar
+impl<'me, 'info> From<LegacyCreateAccounts<'me, 'info>> for CreateAccounts<'me, 'info> {
+    fn from(
+        LegacyCreateAccounts {
+            funding_account,
+            associated_token_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+            ..
+        }: LegacyCreateAccounts<'me, 'info>,
+    ) -> Self {
+        Self {
+            funding_account,
+            associated_token_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+        }
+    }
+}
+
+pub fn legacy_create_verify_account_privileges(
+    accounts: &LegacyCreateAccounts<'_, '_>,
+) -> Result<(), ProgramError> {
+    for should_be_writable in [accounts.funding_account, accounts.associated_token_account] {
+        if !should_be_writable.is_writable {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    for should_be_signer in [accounts.funding_account] {
+        if !should_be_signer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }
+    Ok(())
+}
+
+ideally::impl_verify_accounts!(
+    LegacyCreateAccounts,
+    LegacyCreateKeys,
+    legacy_create_verify_account_privileges,
+    LegacyCreateAccountField {
+        FundingAccount: funding_account,
+        AssociatedTokenAccount: associated_token_account,
+        Wallet: wallet,
+        Mint: mint,
+        SystemProgram: system_program,
+        TokenProgram: token_program,
+        Rent: rent,
+    }
+);
+
+/// [`super::create::CREATE_KEY_MISMATCH_ERRS`] for the legacy layout
+pub const LEGACY_CREATE_KEY_MISMATCH_ERRS: KeyMismatchErrs<LegacyCreateAccountField> =
+    KeyMismatchErrs {
+        rules: &[
+            KeyMismatchRule {
+                field: LegacyCreateAccountField::AssociatedTokenAccount,
+                err: ProgramError::InvalidSeeds,
+                msg: Some("Error: Associated address does not match seed derivation"),
+            },
+            KeyMismatchRule {
+                field: LegacyCreateAccountField::Rent,
+                err: ProgramError::InvalidArgument,
+                msg: Some("Error: Rent sysvar does not match"),
+            },
+        ],
+        default: ProgramError::InvalidAccountData,
+    };
+
+/// Builds a legacy `Create` instruction: no instruction data, which the program
+/// treats as `Create`, and the rent sysvar as the 7th account
+pub fn legacy_create_ix(keys: LegacyCreateKeys) -> Instruction {
+    let metas: [AccountMeta; LEGACY_CREATE_IX_ACCOUNTS_LEN] = (&keys).into();
+    Instruction {
+        program_id: spl_associated_token_account_interface::ID,
+        accounts: Vec::from(metas),
+        data: vec![],
+    }
+}
+
+impl<M: KeyedAccount + ReadonlyAccountOwner> CreateRootAccounts<M> {
+    pub fn resolve_legacy(&self) -> (LegacyCreateKeys, AtaCreatePdaArgs) {
+        self.resolve_token_program().resolve_legacy()
+    }
+
+    pub fn resolve_legacy_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> (LegacyCreateKeys, AtaCreatePdaArgs) {
+        self.resolve_token_program()
+            .resolve_legacy_with_program_id(program_id)
+    }
+}
+
+impl CreateKeysTokenProgramResolved {
+    pub fn resolve_legacy(&self) -> (LegacyCreateKeys, AtaCreatePdaArgs) {
+        to_legacy(self.resolve_infallible())
+    }
+
+    pub fn resolve_legacy_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> (LegacyCreateKeys, AtaCreatePdaArgs) {
+        to_legacy(self.resolve_infallible_with_program_id(program_id))
+    }
+}
+
+fn to_legacy(
+    (
+        CreateKeys {
+            funding_account,
+            associated_token_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+        },
+        create_pda_args,
+    ): (CreateKeys, AtaCreatePdaArgs),
+) -> (LegacyCreateKeys, AtaCreatePdaArgs) {
+    (
+        LegacyCreateKeys {
+            funding_account,
+            associated_token_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+            rent: sysvar::rent::ID,
+        },
+        create_pda_args,
+    )
+}
@@ -1,2 +1,6 @@
 pub mod create;
+pub mod create_with_bump;
+pub mod legacy_create;
+#[cfg(feature = "client")]
+pub mod off_curve;
 pub mod recover_nested;
@@ -0,0 +1,191 @@
+//! Rejecting off-curve wallets, same as upstream clients' `allowOwnerOffCurve`.
+//!
+//! An associated token account of an off-curve wallet e.g. a PDA can only be used
+//! by the program that owns the PDA, and deriving one by mistake is how
+//! nested associated token accounts are usually created.
+//!
+//! The program itself accepts off-curve wallets, so these errors are only ever
+//! returned off-chain by the library. Off-chain only: [`Pubkey::is_on_curve`]
+//! is not available on-chain, hence the `client` feature.
+
+use core::fmt::Display;
+
+use ideally::{Resolve, ResolveWithProgramId};
+use solana_program::pubkey::Pubkey;
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner};
+
+use super::{
+    create::{CreateKeysTokenProgramResolved, CreateRootAccounts},
+    create_with_bump::CreateWithBumpRootAccounts,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WalletOffCurveError {
+    pub wallet: Pubkey,
+}
+
+impl Display for WalletOffCurveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Wallet {} is off-curve, e.g. a PDA", self.wallet)
+    }
+}
+
+impl std::error::Error for WalletOffCurveError {}
+
+/// Clients that only want to warn users can call this directly
+pub fn check_wallet_on_curve(wallet: &Pubkey) -> Result<(), WalletOffCurveError> {
+    if wallet.is_on_curve() {
+        Ok(())
+    } else {
+        Err(WalletOffCurveError { wallet: *wallet })
+    }
+}
+
+/// Resolvers whose wallet is a free account
+pub trait FreeWallet {
+    fn wallet(&self) -> &Pubkey;
+}
+
+impl<M: KeyedAccount + ReadonlyAccountOwner> FreeWallet for CreateRootAccounts<M> {
+    fn wallet(&self) -> &Pubkey {
+        &self.wallet
+    }
+}
+
+impl FreeWallet for CreateKeysTokenProgramResolved {
+    fn wallet(&self) -> &Pubkey {
+        &self.wallet
+    }
+}
+
+impl<M: KeyedAccount + ReadonlyAccountOwner> FreeWallet for CreateWithBumpRootAccounts<M> {
+    fn wallet(&self) -> &Pubkey {
+        &self.root.wallet
+    }
+}
+
+/// Wraps a resolver to reject off-curve wallets with [`check_wallet_on_curve`]
+/// before resolving, unless `allow_owner_off_curve`.
+///
+/// Use [`Resolve`], or [`Self::checked`] for the resolver's other resolve fns.
+///
+/// Example:
+///
+/// ```rust ignore
+/// let (keys, _) = OffCurveCheck {
+///     resolver: CreateKeysTokenProgramResolved { .. },
+///     allow_owner_off_curve: false,
+/// }
+/// .checked()?
+/// .resolve_idempotent();
+/// ```
+pub struct OffCurveCheck<R> {
+    pub resolver: R,
+    pub allow_owner_off_curve: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffCurveCheckError<E> {
+    Resolve(E),
+    WalletOffCurve(WalletOffCurveError),
+}
+
+impl<R: FreeWallet> OffCurveCheck<R> {
+    /// Returns the wrapped resolver if its wallet passes the check
+    pub fn checked(&self) -> Result<&R, WalletOffCurveError> {
+        if !self.allow_owner_off_curve {
+            check_wallet_on_curve(self.resolver.wallet())?;
+        }
+        Ok(&self.resolver)
+    }
+}
+
+impl<R: FreeWallet + Resolve> Resolve for OffCurveCheck<R> {
+    type Keys = R::Keys;
+
+    type Data = R::Data;
+
+    type Err = OffCurveCheckError<R::Err>;
+
+    fn resolve(&self) -> Result<(R::Keys, R::Data), Self::Err> {
+        self.checked()
+            .map_err(OffCurveCheckError::WalletOffCurve)?
+            .resolve()
+            .map_err(OffCurveCheckError::Resolve)
+    }
+}
+
+impl<R: FreeWallet + ResolveWithProgramId> ResolveWithProgramId for OffCurveCheck<R> {
+    fn resolve_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<(R::Keys, R::Data), Self::Err> {
+        self.checked()
+            .map_err(OffCurveCheckError::WalletOffCurve)?
+            .resolve_with_program_id(program_id)
+            .map_err(OffCurveCheckError::Resolve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(wallet: Pubkey) -> CreateKeysTokenProgramResolved {
+        CreateKeysTokenProgramResolved {
+            funding_account: Pubkey::new_unique(),
+            wallet,
+            mint: Pubkey::new_unique(),
+            token_program: spl_token::ID,
+        }
+    }
+
+    fn pda_wallet() -> Pubkey {
+        Pubkey::find_program_address(&[b"wallet"], &Pubkey::new_unique()).0
+    }
+
+    #[test]
+    fn rejects_off_curve_wallet() {
+        let wallet = pda_wallet();
+        let check = OffCurveCheck {
+            resolver: resolver(wallet),
+            allow_owner_off_curve: false,
+        };
+        let err = OffCurveCheckError::WalletOffCurve(WalletOffCurveError { wallet });
+        assert_eq!(check.resolve().unwrap_err(), err);
+        assert_eq!(check.checked().err(), Some(WalletOffCurveError { wallet }));
+    }
+
+    #[test]
+    fn allows_off_curve_wallet() {
+        let wallet = pda_wallet();
+        let check = OffCurveCheck {
+            resolver: resolver(wallet),
+            allow_owner_off_curve: true,
+        };
+        assert_eq!(check.resolve().unwrap().0.wallet, wallet);
+        assert_eq!(
+            check.checked().unwrap().resolve_idempotent().0.wallet,
+            wallet
+        );
+        assert_eq!(
+            check.checked().unwrap().resolve_with_bump().0.wallet,
+            wallet
+        );
+    }
+
+    #[test]
+    fn accepts_on_curve_wallet() {
+        let wallet = Pubkey::from([1; 32]);
+        assert!(wallet.is_on_curve());
+        let check = OffCurveCheck {
+            resolver: resolver(wallet),
+            allow_owner_off_curve: false,
+        };
+        assert_eq!(check.resolve().unwrap().0.wallet, wallet);
+        assert_eq!(
+            check.checked().unwrap().resolve_idempotent().0.wallet,
+            wallet
+        );
+    }
+}
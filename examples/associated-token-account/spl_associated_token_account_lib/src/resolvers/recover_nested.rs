@@ -1,41 +1,210 @@
-use solana_program::{program_error::ProgramError, pubkey::Pubkey};
-use solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner};
-use spl_associated_token_account_interface::RecoverNestedKeys;
+use ideally::{
+    pinned_key, IdeallyError, KeyMismatch, KeyMismatchErrs, KeyMismatchRule, Resolve,
+    ResolveInfallible, ResolvePinned, ResolvePinnedWithProgramId, ResolveWithProgramId, Resolver,
+    Stage,
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use solana_readonly_account::{KeyedAccount, ReadonlyAccountData, ReadonlyAccountOwner};
+use spl_associated_token_account_interface::{
+    RecoverNestedAccountField, RecoverNestedFreeAccounts, RecoverNestedKeys,
+    SplAssociatedTokenAccountError,
+};
+use spl_token_2022::{extension::StateWithExtensions, state::Account};
 
+#[cfg(feature = "client")]
+use crate::pda::AtaIndex;
 use crate::pda::{AtaCreatePdaArgs, AtaFindPdaArgs};
 
-pub struct RecoverNestedRootAccounts<A: KeyedAccount + ReadonlyAccountOwner> {
+pub type RecoverNestedError =
+    IdeallyError<RecoverNestedAccountField, SplAssociatedTokenAccountError>;
+
+/// Errors returned by the upstream program for mismatched `RecoverNested` accounts
+pub const RECOVER_NESTED_KEY_MISMATCH_ERRS: KeyMismatchErrs<RecoverNestedAccountField> =
+    KeyMismatchErrs {
+        rules: &[
+            KeyMismatchRule {
+                field: RecoverNestedAccountField::OwnerAssociatedTokenAccount,
+                err: ProgramError::InvalidSeeds,
+                msg: Some("Error: Owner associated address does not match seed derivation"),
+            },
+            KeyMismatchRule {
+                field: RecoverNestedAccountField::Nested,
+                err: ProgramError::InvalidSeeds,
+                msg: Some("Error: Nested associated address does not match seed derivation"),
+            },
+            KeyMismatchRule {
+                field: RecoverNestedAccountField::WalletAssociatedTokenAccount,
+                err: ProgramError::InvalidSeeds,
+                msg: Some("Error: Destination associated address does not match seed derivation"),
+            },
+            KeyMismatchRule {
+                field: RecoverNestedAccountField::TokenProgram,
+                err: ProgramError::IllegalOwner,
+                msg: Some("Incorrect token program"),
+            },
+        ],
+        default: ProgramError::InvalidAccountData,
+    };
+
+/// The two mints can be different account types e.g. an `AccountInfo` and
+/// a cached off-chain snapshot
+pub struct RecoverNestedRootAccounts<
+    O: KeyedAccount + ReadonlyAccountOwner,
+    N: KeyedAccount + ReadonlyAccountOwner,
+> {
     pub wallet: Pubkey,
-    pub owner_token_account_mint: A,
-    pub nested_mint: A,
+    pub owner_token_account_mint: O,
+    pub nested_mint: N,
+}
+
+impl<'me, 'info> RecoverNestedRootAccounts<&'me AccountInfo<'info>, &'me AccountInfo<'info>> {
+    /// Extracts the free accounts from the `RecoverNested` instruction's accounts.
+    ///
+    /// Returns [`ProgramError::NotEnoughAccountKeys`] if there are not enough accounts
+    pub fn from_account_infos(accounts: &'me [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let RecoverNestedFreeAccounts {
+            nested_mint,
+            owner_token_account_mint,
+            wallet,
+        } = RecoverNestedFreeAccounts::from_account_infos(accounts)?;
+        Ok(Self {
+            wallet: *wallet.key,
+            owner_token_account_mint,
+            nested_mint,
+        })
+    }
 }
 
-impl<A: KeyedAccount + ReadonlyAccountOwner> RecoverNestedRootAccounts<A> {
+impl<O: KeyedAccount + ReadonlyAccountOwner, N: KeyedAccount + ReadonlyAccountOwner>
+    RecoverNestedRootAccounts<O, N>
+{
     /// Determins the spl-token program ID to use from the program owners of
     /// owner_token_account_mint and nested_mint
-    /// Returns ProgramError::IllegalOwner if the 2 dont match
-    pub fn det_token_program(&self) -> Result<Pubkey, ProgramError> {
+    /// Returns ProgramError::IllegalOwner for token_program,
+    /// with nested_mint's owner as the actual key, if the 2 dont match
+    pub fn det_token_program(&self) -> Result<Pubkey, RecoverNestedError> {
         let owner_token_program = self.owner_token_account_mint.owner();
         let nested_token_program = self.nested_mint.owner();
         if owner_token_program != nested_token_program {
-            return Err(ProgramError::IllegalOwner);
+            return Err(IdeallyError::key_mismatch(
+                Stage::ResolveOwner,
+                KeyMismatch {
+                    field: RecoverNestedAccountField::TokenProgram,
+                    actual: *nested_token_program,
+                    expected: *owner_token_program,
+                },
+                ProgramError::IllegalOwner,
+            ));
         }
         Ok(*owner_token_program)
     }
 
-    /// .1 is owner_token_account signer seeds args
-    pub fn resolve(&self) -> Result<(RecoverNestedKeys, AtaCreatePdaArgs), ProgramError> {
-        let token_program = self.det_token_program()?;
-        let root_keys = RecoverNestedRootKeys {
+    pub fn resolve_token_program(&self) -> Result<RecoverNestedRootKeys, RecoverNestedError> {
+        self.resolve_token_program_pinned(&[])
+    }
+
+    /// [`Self::resolve_token_program`] that uses the pinned token program, if any,
+    /// instead of reading the mints' owners
+    pub fn resolve_token_program_pinned(
+        &self,
+        pinned: &[(RecoverNestedAccountField, Pubkey)],
+    ) -> Result<RecoverNestedRootKeys, RecoverNestedError> {
+        let token_program = match pinned_key::<RecoverNestedKeys>(pinned, "token_program") {
+            Some(token_program) => token_program,
+            None => self.det_token_program()?,
+        };
+        Ok(RecoverNestedRootKeys {
             wallet: self.wallet,
             owner_token_account_mint: *self.owner_token_account_mint.key(),
             nested_mint: *self.nested_mint.key(),
             token_program,
-        };
-        Ok(root_keys.resolve())
+        })
     }
 }
 
+impl<O: KeyedAccount + ReadonlyAccountOwner, N: KeyedAccount + ReadonlyAccountOwner> Resolve
+    for RecoverNestedRootAccounts<O, N>
+{
+    type Keys = RecoverNestedKeys;
+    type Data = AtaCreatePdaArgs;
+    type Err = RecoverNestedError;
+
+    /// .1 is owner_token_account signer seeds args
+    fn resolve(&self) -> Result<(RecoverNestedKeys, AtaCreatePdaArgs), RecoverNestedError> {
+        Ok(self.resolve_token_program()?.resolve_infallible())
+    }
+}
+
+impl<O: KeyedAccount + ReadonlyAccountOwner, N: KeyedAccount + ReadonlyAccountOwner>
+    ResolveWithProgramId for RecoverNestedRootAccounts<O, N>
+{
+    fn resolve_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<(RecoverNestedKeys, AtaCreatePdaArgs), RecoverNestedError> {
+        Ok(self
+            .resolve_token_program()?
+            .resolve_infallible_with_program_id(program_id))
+    }
+}
+
+impl<O: KeyedAccount + ReadonlyAccountOwner, N: KeyedAccount + ReadonlyAccountOwner> ResolvePinned
+    for RecoverNestedRootAccounts<O, N>
+{
+    const FREE_ACCOUNTS: &'static [&'static str] =
+        &["nested_mint", "owner_token_account_mint", "wallet"];
+
+    fn resolve_pinned(
+        &self,
+        pinned: &[(RecoverNestedAccountField, Pubkey)],
+    ) -> Result<(RecoverNestedKeys, AtaCreatePdaArgs), RecoverNestedError> {
+        let root_keys = self.resolve_token_program_pinned(pinned)?;
+        Ok(root_keys
+            .resolve_pinned(pinned)
+            .unwrap_or_else(|e| match e {}))
+    }
+}
+
+impl<O: KeyedAccount + ReadonlyAccountOwner, N: KeyedAccount + ReadonlyAccountOwner>
+    ResolvePinnedWithProgramId for RecoverNestedRootAccounts<O, N>
+{
+    fn resolve_pinned_with_program_id(
+        &self,
+        pinned: &[(RecoverNestedAccountField, Pubkey)],
+        program_id: &Pubkey,
+    ) -> Result<(RecoverNestedKeys, AtaCreatePdaArgs), RecoverNestedError> {
+        let root_keys = self.resolve_token_program_pinned(pinned)?;
+        Ok(root_keys
+            .resolve_pinned_with_program_id(pinned, program_id)
+            .unwrap_or_else(|e| match e {}))
+    }
+}
+
+/// Resolve::Data is owner_token_account signer seeds args
+#[derive(Resolver)]
+#[resolver(keys = RecoverNestedKeys)]
+#[resolver(pda(
+    owner_associated_token_account = AtaFindPdaArgs {
+        wallet,
+        mint: owner_token_account_mint,
+        token_program,
+    },
+    signer
+))]
+#[resolver(pda(
+    nested = AtaFindPdaArgs {
+        wallet: owner_associated_token_account,
+        mint: nested_mint,
+        token_program,
+    }
+))]
+#[resolver(pda(
+    wallet_associated_token_account = AtaFindPdaArgs {
+        wallet,
+        mint: nested_mint,
+        token_program,
+    }
+))]
 pub struct RecoverNestedRootKeys {
     pub wallet: Pubkey,
     pub owner_token_account_mint: Pubkey,
@@ -44,43 +213,429 @@ pub struct RecoverNestedRootKeys {
 }
 
 impl RecoverNestedRootKeys {
-    /// .1 is owner_token_account signer seeds args
-    pub fn resolve(&self) -> (RecoverNestedKeys, AtaCreatePdaArgs) {
-        let find_owner_token_account_args = AtaFindPdaArgs {
-            wallet: self.wallet,
-            mint: self.owner_token_account_mint,
-            token_program: self.token_program,
+    /// Returns the keys to recover `nested` with if it is a nested associated token account,
+    /// i.e. the associated token account of `owner_ata`, which is itself
+    /// the associated token account of some wallet.
+    ///
+    /// Returns `Ok(None)` if it is not, and an error if either account's data
+    /// is not a token account.
+    pub fn detect<
+        N: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData,
+        O: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData,
+    >(
+        nested: &N,
+        owner_ata: &O,
+    ) -> Result<Option<Self>, ProgramError> {
+        Self::detect_with_program_id(
+            nested,
+            owner_ata,
+            &spl_associated_token_account_interface::ID,
+        )
+    }
+
+    /// [`Self::detect`] for a deployment of the program at `program_id`
+    pub fn detect_with_program_id<
+        N: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData,
+        O: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData,
+    >(
+        nested: &N,
+        owner_ata: &O,
+        program_id: &Pubkey,
+    ) -> Result<Option<Self>, ProgramError> {
+        let token_program = *nested.owner();
+        if *owner_ata.owner() != token_program {
+            return Ok(None);
+        }
+        let (nested_mint, nested_owner) = token_account_mint_and_owner(nested)?;
+        if nested_owner != *owner_ata.key() {
+            return Ok(None);
+        }
+        let (owner_token_account_mint, wallet) = token_account_mint_and_owner(owner_ata)?;
+        let keys = Self {
+            wallet,
+            owner_token_account_mint,
+            nested_mint,
+            token_program,
+        };
+        Ok(keys
+            .is_nested(nested.key(), owner_ata.key(), program_id)
+            .then_some(keys))
+    }
+
+    /// [`Self::detect`] without the owner's account data, looking the owner up
+    /// in an [`AtaIndex`] of candidate wallets and mints instead
+    #[cfg(feature = "client")]
+    pub fn detect_with_index<N: KeyedAccount + ReadonlyAccountOwner + ReadonlyAccountData>(
+        nested: &N,
+        index: &AtaIndex,
+    ) -> Result<Option<Self>, ProgramError> {
+        let (nested_mint, nested_owner) = token_account_mint_and_owner(nested)?;
+        let AtaFindPdaArgs {
+            wallet,
+            token_program,
+            mint: owner_token_account_mint,
+        } = match index.lookup(&nested_owner) {
+            Some(args) => *args,
+            None => return Ok(None),
         };
-        let (owner_associated_token_account, bump) =
-            find_owner_token_account_args.get_associated_token_address_and_bump_seed();
-        let find_nested_token_account_args = AtaFindPdaArgs {
-            wallet: owner_associated_token_account,
-            mint: self.nested_mint,
-            token_program: self.token_program,
+        if token_program != *nested.owner() {
+            return Ok(None);
+        }
+        let keys = Self {
+            wallet,
+            owner_token_account_mint,
+            nested_mint,
+            token_program,
         };
-        let (nested, _) =
-            find_nested_token_account_args.get_associated_token_address_and_bump_seed();
-        let find_wallet_ata_args = AtaFindPdaArgs {
-            wallet: self.wallet,
-            mint: self.nested_mint,
-            token_program: self.token_program,
+        Ok(keys
+            .is_nested(nested.key(), &nested_owner, index.program_id())
+            .then_some(keys))
+    }
+
+    fn is_nested(&self, nested: &Pubkey, owner_ata: &Pubkey, program_id: &Pubkey) -> bool {
+        let (keys, _) = self.resolve_infallible_with_program_id(program_id);
+        keys.nested == *nested && keys.owner_associated_token_account == *owner_ata
+    }
+}
+
+/// Returns (mint, owner) of a spl-token or Token-2022 token account
+pub(crate) fn token_account_mint_and_owner<A: ReadonlyAccountData>(
+    account: &A,
+) -> Result<(Pubkey, Pubkey), ProgramError> {
+    let data = account.data();
+    let token_account = StateWithExtensions::<Account>::unpack(&data)?;
+    Ok((token_account.base.mint, token_account.base.owner))
+}
+
+#[cfg(test)]
+mod tests {
+    use ideally::{FindPda, OverridesError, WithOverrides};
+    use solana_program::program_pack::Pack;
+    use spl_token::state::AccountState;
+
+    use super::*;
+
+    struct TokenAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        data: Vec<u8>,
+    }
+
+    impl TokenAccount {
+        fn new(key: Pubkey, token_program: Pubkey, mint: Pubkey, owner: Pubkey) -> Self {
+            let mut data = vec![0; spl_token::state::Account::LEN];
+            spl_token::state::Account::pack(
+                spl_token::state::Account {
+                    mint,
+                    owner,
+                    state: AccountState::Initialized,
+                    ..Default::default()
+                },
+                &mut data,
+            )
+            .unwrap();
+            Self {
+                key,
+                owner: token_program,
+                data,
+            }
+        }
+    }
+
+    impl KeyedAccount for TokenAccount {
+        fn key(&self) -> &Pubkey {
+            &self.key
+        }
+    }
+
+    impl ReadonlyAccountOwner for TokenAccount {
+        fn owner(&self) -> &Pubkey {
+            &self.owner
+        }
+    }
+
+    impl ReadonlyAccountData for TokenAccount {
+        type SliceDeref<'s> = Vec<u8>;
+
+        type DataDeref<'d> = &'d Vec<u8>;
+
+        fn data(&self) -> Self::DataDeref<'_> {
+            &self.data
+        }
+    }
+
+    fn ata(wallet: Pubkey, token_program: Pubkey, mint: Pubkey) -> Pubkey {
+        AtaFindPdaArgs {
+            wallet,
+            token_program,
+            mint,
+        }
+        .find_pda()
+        .0
+    }
+
+    /// Returns (nested, owner_ata, expected keys)
+    fn nested(token_program: Pubkey) -> (TokenAccount, TokenAccount, RecoverNestedRootKeys) {
+        let keys = RecoverNestedRootKeys {
+            wallet: Pubkey::new_unique(),
+            owner_token_account_mint: Pubkey::new_unique(),
+            nested_mint: Pubkey::new_unique(),
+            token_program,
         };
-        let (wallet_associated_token_account, _) =
-            find_wallet_ata_args.get_associated_token_address_and_bump_seed();
-        (
-            RecoverNestedKeys {
-                wallet: self.wallet,
-                owner_token_account_mint: self.owner_token_account_mint,
-                nested_mint: self.nested_mint,
-                token_program: self.token_program,
-                nested,
-                owner_associated_token_account,
-                wallet_associated_token_account,
+        let owner_ata_key = ata(keys.wallet, token_program, keys.owner_token_account_mint);
+        let owner_ata = TokenAccount::new(
+            owner_ata_key,
+            token_program,
+            keys.owner_token_account_mint,
+            keys.wallet,
+        );
+        let nested = TokenAccount::new(
+            ata(owner_ata_key, token_program, keys.nested_mint),
+            token_program,
+            keys.nested_mint,
+            owner_ata_key,
+        );
+        (nested, owner_ata, keys)
+    }
+
+    fn assert_keys_eq(actual: &RecoverNestedRootKeys, expected: &RecoverNestedRootKeys) {
+        assert_eq!(actual.wallet, expected.wallet);
+        assert_eq!(
+            actual.owner_token_account_mint,
+            expected.owner_token_account_mint
+        );
+        assert_eq!(actual.nested_mint, expected.nested_mint);
+        assert_eq!(actual.token_program, expected.token_program);
+    }
+
+    #[cfg(feature = "client")]
+    fn index_of(keys: &RecoverNestedRootKeys) -> AtaIndex {
+        let mut index = AtaIndex::default();
+        index.add_wallet(keys.wallet);
+        index.add_mint(keys.owner_token_account_mint);
+        index
+    }
+
+    #[test]
+    fn detect_nested() {
+        for token_program in [spl_token::ID, spl_token_2022::ID] {
+            let (nested, owner_ata, expected) = nested(token_program);
+            let detected = RecoverNestedRootKeys::detect(&nested, &owner_ata)
+                .unwrap()
+                .unwrap();
+            assert_keys_eq(&detected, &expected);
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn detect_nested_with_index() {
+        for token_program in [spl_token::ID, spl_token_2022::ID] {
+            let (nested, _, expected) = nested(token_program);
+            let detected = RecoverNestedRootKeys::detect_with_index(&nested, &index_of(&expected))
+                .unwrap()
+                .unwrap();
+            assert_keys_eq(&detected, &expected);
+        }
+    }
+
+    #[test]
+    fn detect_not_nested() {
+        let (nested_account, owner_ata, _) = nested(spl_token::ID);
+
+        // owned by owner_ata, but not its associated token account
+        let not_ata = TokenAccount {
+            key: Pubkey::new_unique(),
+            ..nested_account
+        };
+        assert!(RecoverNestedRootKeys::detect(&not_ata, &owner_ata)
+            .unwrap()
+            .is_none());
+
+        // owned by some other token account
+        let (other_nested, other_owner_ata, _) = nested(spl_token::ID);
+        assert!(RecoverNestedRootKeys::detect(&other_nested, &owner_ata)
+            .unwrap()
+            .is_none());
+
+        // owner_ata data is not a token account
+        let not_token_account = TokenAccount {
+            data: vec![1; 3],
+            ..other_owner_ata
+        };
+        assert!(RecoverNestedRootKeys::detect(&other_nested, &not_token_account).is_err());
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn detect_not_nested_with_index() {
+        let (nested_account, _, expected) = nested(spl_token::ID);
+        let index = index_of(&expected);
+
+        // owned by owner_ata, but not its associated token account
+        let not_ata = TokenAccount {
+            key: Pubkey::new_unique(),
+            ..nested_account
+        };
+        assert!(RecoverNestedRootKeys::detect_with_index(&not_ata, &index)
+            .unwrap()
+            .is_none());
+
+        // owned by some other token account
+        let (other_nested, _, _) = nested(spl_token::ID);
+        assert!(
+            RecoverNestedRootKeys::detect_with_index(&other_nested, &index)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn detect_mismatched_token_program() {
+        let (mut nested, owner_ata, _) = nested(spl_token::ID);
+        nested.owner = spl_token_2022::ID;
+        assert!(RecoverNestedRootKeys::detect(&nested, &owner_ata)
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn detect_mismatched_token_program_with_index() {
+        let (mut nested, _, expected) = nested(spl_token::ID);
+        nested.owner = spl_token_2022::ID;
+        assert!(
+            RecoverNestedRootKeys::detect_with_index(&nested, &index_of(&expected))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    /// A mint whose account was not fetched
+    struct MintKey(Pubkey);
+
+    impl KeyedAccount for MintKey {
+        fn key(&self) -> &Pubkey {
+            &self.0
+        }
+    }
+
+    impl ReadonlyAccountOwner for MintKey {
+        fn owner(&self) -> &Pubkey {
+            panic!("mint owner read")
+        }
+    }
+
+    /// A fetched mint, only its key and owner are read
+    fn mint(key: Pubkey, token_program: Pubkey) -> TokenAccount {
+        TokenAccount {
+            key,
+            owner: token_program,
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pinned_token_program_does_not_need_mint_accounts() {
+        let program_id = Pubkey::new_unique();
+        let expected = RecoverNestedRootKeys {
+            wallet: Pubkey::new_unique(),
+            owner_token_account_mint: Pubkey::new_unique(),
+            nested_mint: Pubkey::new_unique(),
+            token_program: spl_token_2022::ID,
+        };
+        let resolver = WithOverrides {
+            resolver: RecoverNestedRootAccounts {
+                wallet: expected.wallet,
+                owner_token_account_mint: MintKey(expected.owner_token_account_mint),
+                nested_mint: MintKey(expected.nested_mint),
             },
-            AtaCreatePdaArgs {
-                find: find_owner_token_account_args,
-                bump: [bump],
+            overrides: &[(RecoverNestedAccountField::TokenProgram, spl_token_2022::ID)],
+        };
+        for (res, program_id) in [
+            (
+                resolver.resolve(),
+                spl_associated_token_account_interface::ID,
+            ),
+            (resolver.resolve_with_program_id(&program_id), program_id),
+        ] {
+            let (keys, args) = res.unwrap();
+            let (expected_keys, expected_args) =
+                expected.resolve_infallible_with_program_id(&program_id);
+            assert_eq!(keys.token_program, spl_token_2022::ID);
+            assert_eq!(keys.nested, expected_keys.nested);
+            assert_eq!(
+                keys.owner_associated_token_account,
+                expected_keys.owner_associated_token_account
+            );
+            assert_eq!(
+                keys.wallet_associated_token_account,
+                expected_keys.wallet_associated_token_account
+            );
+            assert_eq!(args, expected_args);
+        }
+    }
+
+    #[test]
+    fn pinned_owner_associated_token_account() {
+        let wallet = Pubkey::new_unique();
+        let owner_token_account_mint = Pubkey::new_unique();
+        let nested_mint = Pubkey::new_unique();
+        let resolver = || RecoverNestedRootAccounts {
+            wallet,
+            owner_token_account_mint: mint(owner_token_account_mint, spl_token::ID),
+            nested_mint: mint(nested_mint, spl_token::ID),
+        };
+        let (expected, _) = resolver().resolve().unwrap();
+
+        let (keys, _) = WithOverrides {
+            resolver: resolver(),
+            overrides: &[(
+                RecoverNestedAccountField::OwnerAssociatedTokenAccount,
+                expected.owner_associated_token_account,
+            )],
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(keys.nested, expected.nested);
+
+        let wrong = Pubkey::new_unique();
+        let err = WithOverrides {
+            resolver: resolver(),
+            overrides: &[(
+                RecoverNestedAccountField::OwnerAssociatedTokenAccount,
+                wrong,
+            )],
+        }
+        .resolve()
+        .err();
+        assert_eq!(
+            err,
+            Some(OverridesError::Conflict(KeyMismatch {
+                field: RecoverNestedAccountField::OwnerAssociatedTokenAccount,
+                actual: wrong,
+                expected: expected.owner_associated_token_account,
+            }))
+        );
+    }
+
+    #[test]
+    fn pinned_free_account_rejected() {
+        let err = WithOverrides {
+            resolver: RecoverNestedRootAccounts {
+                wallet: Pubkey::new_unique(),
+                owner_token_account_mint: MintKey(Pubkey::new_unique()),
+                nested_mint: MintKey(Pubkey::new_unique()),
             },
-        )
+            overrides: &[(RecoverNestedAccountField::NestedMint, Pubkey::new_unique())],
+        }
+        .resolve()
+        .err();
+        assert_eq!(
+            err,
+            Some(OverridesError::FreeAccount(
+                RecoverNestedAccountField::NestedMint
+            ))
+        );
     }
 }
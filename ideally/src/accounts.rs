@@ -0,0 +1,65 @@
+use solana_program::program_error::ProgramError;
+
+/// Returns the first `N` accounts as an array e.g. to convert into a
+/// solores-generated `*Accounts` struct. Extra accounts are ignored.
+///
+/// Returns [`ProgramError::NotEnoughAccountKeys`] if there are fewer than `N` accounts.
+pub fn accounts_array<const N: usize, T>(accounts: &[T]) -> Result<&[T; N], ProgramError> {
+    accounts
+        .get(..N)
+        .and_then(|prefix| prefix.try_into().ok())
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+/// Generates a struct of the free accounts of a solores-generated `*Accounts` struct,
+/// i.e. the accounts marked `"isFree": true` in the IDL, and its
+/// `from_account_infos` constructor for use in the instruction's processor.
+///
+/// Generated into the interface crate by `ideally new`.
+///
+/// Example:
+///
+/// ```rust ignore
+/// ideally::free_accounts!(
+///     CreateAccounts,
+///     CREATE_IX_ACCOUNTS_LEN,
+///     CreateFreeAccounts {
+///         funding_account,
+///         wallet,
+///         mint,
+///     }
+/// );
+///
+/// let CreateFreeAccounts { funding_account, wallet, mint } =
+///     CreateFreeAccounts::from_account_infos(accounts)?;
+/// ```
+#[macro_export]
+macro_rules! free_accounts {
+    (
+        $accounts:ident,
+        $accounts_len:ident,
+        $free:ident { $($member:ident),+ $(,)? }
+    ) => {
+        #[doc = concat!("The free accounts of [`", stringify!($accounts), "`]")]
+        #[derive(Clone, Copy, Debug)]
+        pub struct $free<'me, 'info> {
+            $(pub $member: &'me $crate::solana_program::account_info::AccountInfo<'info>),+
+        }
+
+        impl<'me, 'info> $free<'me, 'info> {
+            /// Extracts the free accounts from the instruction's accounts.
+            ///
+            /// Returns [`ProgramError::NotEnoughAccountKeys`] if there are not enough accounts
+            ///
+            /// [`ProgramError::NotEnoughAccountKeys`]: $crate::solana_program::program_error::ProgramError::NotEnoughAccountKeys
+            pub fn from_account_infos(
+                accounts: &'me [$crate::solana_program::account_info::AccountInfo<'info>],
+            ) -> ::core::result::Result<Self, $crate::solana_program::program_error::ProgramError>
+            {
+                let $accounts { $($member),+, .. } =
+                    $crate::accounts_array::<$accounts_len, _>(accounts)?.into();
+                Ok(Self { $($member),+ })
+            }
+        }
+    };
+}
@@ -0,0 +1,127 @@
+/// Generates a program's `pub fn process_instruction` and its
+/// `no-entrypoint`-gated solana entrypoint.
+///
+/// `process_instruction`:
+/// 1. returns [`ProgramError::IncorrectProgramId`] if the program id is not the given one.
+///    Instead, bind the program id for handlers with `|program_id, accounts|`
+///    and no leading program id to run the program at any address
+/// 2. deserializes the solores-generated `*ProgramIx` enum from instruction data,
+///    or if instruction data is empty, uses the `default` variant or
+///    calls the `empty` handler if either was provided
+/// 3. logs the instruction
+/// 4. routes each variant to its handler, with the accounts bound to the given ident
+///
+/// Use `empty => handler` instead of `default: Variant(args)` for instructions with
+/// no instruction data that do not map to a single variant, e.g. legacy account layouts.
+///
+/// Must be invoked at most once per crate, and the crate must declare a `no-entrypoint` feature.
+///
+/// Example:
+///
+/// ```rust ignore
+/// ideally::program_entrypoint!(
+///     crate::ID,
+///     SplAssociatedTokenAccountProgramIx,
+///     |accounts| {
+///         Create(_) => process_create(accounts, CreateMode::Always),
+///         CreateIdempotent(_) => process_create(accounts, CreateMode::Idempotent),
+///         RecoverNested(_) => process_recover_nested(accounts),
+///     },
+///     default: Create(CreateIxArgs {}),
+/// );
+/// ```
+///
+/// Deployable at any address, with handlers resolving PDAs for the actual program id:
+///
+/// ```rust ignore
+/// ideally::program_entrypoint!(
+///     SplAssociatedTokenAccountProgramIx,
+///     |program_id, accounts| {
+///         Create(_) => process_create(program_id, accounts, CreateMode::Always),
+///         ..
+///     },
+/// );
+/// ```
+///
+/// [`ProgramError::IncorrectProgramId`]: solana_program::program_error::ProgramError::IncorrectProgramId
+#[macro_export]
+macro_rules! program_entrypoint {
+    (
+        $ix:ident,
+        |$program_id:ident, $accounts:ident| { $($variant:ident($args:pat) => $handler:expr),+ $(,)? }
+        $(, default: $default_variant:ident($default_args:expr))?
+        $(, empty => $empty_handler:expr)?
+        $(,)?
+    ) => {
+        $crate::program_entrypoint!(
+            @impl $program_id, $accounts, $ix,
+            { $($variant($args) => $handler),+ }
+            { $($default_variant($default_args))? }
+            { $($empty_handler)? }
+        );
+    };
+    (
+        $expected_program_id:expr,
+        $ix:ident,
+        |$accounts:ident| { $($variant:ident($args:pat) => $handler:expr),+ $(,)? }
+        $(, default: $default_variant:ident($default_args:expr))?
+        $(, empty => $empty_handler:expr)?
+        $(,)?
+    ) => {
+        $crate::program_entrypoint!(
+            @impl program_id, $accounts, $ix,
+            { $($variant($args) => $handler),+ }
+            { $($default_variant($default_args))? }
+            { $($empty_handler)? }
+            check $expected_program_id
+        );
+    };
+    (
+        @impl $program_id:ident, $accounts:ident, $ix:ident,
+        { $($variant:ident($args:pat) => $handler:expr),+ }
+        { $($default_variant:ident($default_args:expr))? }
+        { $($empty_handler:expr)? }
+        $(check $expected_program_id:expr)?
+    ) => {
+        #[cfg(not(feature = "no-entrypoint"))]
+        $crate::solana_program::entrypoint!(process_instruction);
+
+        pub fn process_instruction(
+            $program_id: &$crate::solana_program::pubkey::Pubkey,
+            $accounts: &[$crate::solana_program::account_info::AccountInfo],
+            input: &[u8],
+        ) -> $crate::solana_program::entrypoint::ProgramResult {
+            $(
+                if *$program_id != $expected_program_id {
+                    return Err($crate::solana_program::program_error::ProgramError::IncorrectProgramId);
+                }
+            )?
+
+            $(
+                if input.is_empty() {
+                    return $empty_handler;
+                }
+            )?
+
+            let instruction = $crate::program_entrypoint!(
+                @deserialize $ix, input $(, $default_variant($default_args))?
+            );
+
+            $crate::solana_program::msg!("{:?}", instruction);
+
+            match instruction {
+                $($ix::$variant($args) => $handler),+
+            }
+        }
+    };
+    (@deserialize $ix:ident, $input:ident) => {
+        $ix::deserialize(&mut &$input[..])?
+    };
+    (@deserialize $ix:ident, $input:ident, $default_variant:ident($default_args:expr)) => {
+        if $input.is_empty() {
+            $ix::$default_variant($default_args)
+        } else {
+            $ix::deserialize(&mut &$input[..])?
+        }
+    };
+}
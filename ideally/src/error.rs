@@ -0,0 +1,283 @@
+use core::{convert::Infallible, fmt::Display};
+
+use solana_program::{msg, program_error::ProgramError};
+
+use crate::{KeyMismatch, KeyMismatchErrs, OverridesError, VerifyAccountsError};
+
+/// The step of account resolution and verification that failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// Resolving an account from the program owner of other accounts,
+    /// e.g. the token program from a mint's owner
+    ResolveOwner,
+
+    /// Deriving a PDA
+    ResolvePda,
+
+    /// A pinned key of [`crate::WithOverrides`] was for a free account
+    /// or did not match the resolved key
+    Override,
+
+    /// [`crate::VerifyAccounts::verify_keys`]
+    VerifyKeys,
+
+    /// [`crate::VerifyAccounts::verify_privileges`]
+    VerifyPrivileges,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::ResolveOwner => "resolve owner",
+            Self::ResolvePda => "resolve PDA",
+            Self::Override => "override",
+            Self::VerifyKeys => "verify keys",
+            Self::VerifyPrivileges => "verify privileges",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorCause<F, E> {
+    Program(ProgramError),
+
+    /// The program's custom error e.g. the error enum generated by solores
+    Custom(E),
+
+    /// An account's key did not match the expected key.
+    /// Converts into `err`
+    KeyMismatch {
+        mismatch: KeyMismatch<F>,
+        err: ProgramError,
+    },
+}
+
+impl<F, E: Into<ProgramError>> From<ErrorCause<F, E>> for ProgramError {
+    fn from(cause: ErrorCause<F, E>) -> Self {
+        match cause {
+            ErrorCause::Program(e) => e,
+            ErrorCause::Custom(e) => e.into(),
+            ErrorCause::KeyMismatch { err, .. } => err,
+        }
+    }
+}
+
+impl<F: Display, E: Display> Display for ErrorCause<F, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Program(e) => e.fmt(f),
+            Self::Custom(e) => e.fmt(f),
+            Self::KeyMismatch {
+                mismatch:
+                    KeyMismatch {
+                        field,
+                        actual,
+                        expected,
+                    },
+                err,
+            } => write!(f, "{err}: {field} is {actual}, expected {expected}"),
+        }
+    }
+}
+
+/// Error with full context of where an Ideally program's account resolution
+/// or verification failed.
+///
+/// `F` is the instruction's account field enum generated by
+/// [`crate::impl_verify_accounts`], `E` is the program's custom error type.
+///
+/// Converts into the [`ProgramError`] to return on-chain and implements
+/// [`std::error::Error`] for off-chain use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdeallyError<F, E> {
+    pub stage: Stage,
+
+    /// The account involved, if any
+    pub account: Option<F>,
+
+    pub cause: ErrorCause<F, E>,
+}
+
+impl<F, E> IdeallyError<F, E> {
+    pub fn program(stage: Stage, account: Option<F>, err: ProgramError) -> Self {
+        Self {
+            stage,
+            account,
+            cause: ErrorCause::Program(err),
+        }
+    }
+
+    pub fn custom(stage: Stage, account: Option<F>, err: E) -> Self {
+        Self {
+            stage,
+            account,
+            cause: ErrorCause::Custom(err),
+        }
+    }
+}
+
+impl<F: Copy, E> IdeallyError<F, E> {
+    pub fn key_mismatch(stage: Stage, mismatch: KeyMismatch<F>, err: ProgramError) -> Self {
+        Self {
+            stage,
+            account: Some(mismatch.field),
+            cause: ErrorCause::KeyMismatch { mismatch, err },
+        }
+    }
+}
+
+impl<F: Copy + PartialEq, E> IdeallyError<F, E> {
+    /// Converts a [`VerifyAccountsError`], mapping key mismatches to
+    /// the errors of the program's [`KeyMismatchErrs`] table
+    pub fn verify(e: VerifyAccountsError<F>, errs: &KeyMismatchErrs<F>) -> Self {
+        match e {
+            VerifyAccountsError::KeyMismatch(mismatch) => Self::key_mismatch(
+                Stage::VerifyKeys,
+                mismatch,
+                errs.program_error(&mismatch.field),
+            ),
+            VerifyAccountsError::Privileges(e) => Self::program(Stage::VerifyPrivileges, None, e),
+        }
+    }
+}
+
+impl<F: Display, E: Display> IdeallyError<F, E> {
+    /// Logs the full context with `msg!()` e.g. before returning
+    /// the converted [`ProgramError`] on-chain
+    pub fn log(&self) {
+        msg!("{}", self);
+    }
+
+    /// [`Self::log`]s and returns self, e.g. `.map_err(IdeallyError::logged)?`
+    pub fn logged(self) -> Self {
+        self.log();
+        self
+    }
+}
+
+impl<F: Display, E: Display> Display for IdeallyError<F, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} failed", self.stage)?;
+        if let Some(account) = &self.account {
+            write!(f, " for {account}")?;
+        }
+        write!(f, ": {}", self.cause)
+    }
+}
+
+impl<F: core::fmt::Debug + Display, E: core::fmt::Debug + Display> std::error::Error
+    for IdeallyError<F, E>
+{
+}
+
+impl<F, E: Into<ProgramError>> From<IdeallyError<F, E>> for ProgramError {
+    fn from(e: IdeallyError<F, E>) -> Self {
+        e.cause.into()
+    }
+}
+
+impl<F, E> From<Infallible> for IdeallyError<F, E> {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+/// Key mismatches convert into [`ProgramError::InvalidAccountData`].
+/// Use [`IdeallyError::verify`] to map them through the program's [`KeyMismatchErrs`] instead
+impl<F: Copy, E> From<VerifyAccountsError<F>> for IdeallyError<F, E> {
+    fn from(e: VerifyAccountsError<F>) -> Self {
+        match e {
+            VerifyAccountsError::KeyMismatch(mismatch) => Self::key_mismatch(
+                Stage::VerifyKeys,
+                mismatch,
+                ProgramError::InvalidAccountData,
+            ),
+            VerifyAccountsError::Privileges(e) => Self::program(Stage::VerifyPrivileges, None, e),
+        }
+    }
+}
+
+impl<F: Copy, E, R: Into<IdeallyError<F, E>>> From<OverridesError<R, F>> for IdeallyError<F, E> {
+    fn from(e: OverridesError<R, F>) -> Self {
+        match e {
+            OverridesError::Resolve(e) => e.into(),
+            OverridesError::FreeAccount(field) => {
+                Self::program(Stage::Override, Some(field), ProgramError::InvalidArgument)
+            }
+            OverridesError::Conflict(mismatch) => {
+                Self::key_mismatch(Stage::Override, mismatch, ProgramError::InvalidAccountData)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::pubkey::Pubkey;
+
+    use crate::KeyMismatchRule;
+
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Field {
+        Pda,
+        Other,
+    }
+
+    impl Display for Field {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{self:?}")
+        }
+    }
+
+    const ERRS: KeyMismatchErrs<Field> = KeyMismatchErrs {
+        rules: &[KeyMismatchRule {
+            field: Field::Pda,
+            err: ProgramError::InvalidSeeds,
+            msg: None,
+        }],
+        default: ProgramError::InvalidAccountData,
+    };
+
+    fn mismatch(field: Field) -> KeyMismatch<Field> {
+        KeyMismatch {
+            field,
+            actual: Pubkey::new_unique(),
+            expected: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn verify_maps_key_mismatch_through_rules() {
+        for (field, expected_err) in [
+            (Field::Pda, ProgramError::InvalidSeeds),
+            (Field::Other, ProgramError::InvalidAccountData),
+        ] {
+            let m = mismatch(field);
+            let e: IdeallyError<Field, ProgramError> =
+                IdeallyError::verify(VerifyAccountsError::KeyMismatch(m), &ERRS);
+            assert_eq!(e.stage, Stage::VerifyKeys);
+            assert_eq!(e.account, Some(field));
+            assert_eq!(
+                e.cause,
+                ErrorCause::KeyMismatch {
+                    mismatch: m,
+                    err: expected_err.clone()
+                }
+            );
+            assert_eq!(ProgramError::from(e), expected_err);
+        }
+    }
+
+    #[test]
+    fn display_has_keys() {
+        let m = mismatch(Field::Pda);
+        let e: IdeallyError<Field, ProgramError> =
+            IdeallyError::verify(VerifyAccountsError::KeyMismatch(m), &ERRS);
+        let s = e.to_string();
+        assert!(s.starts_with("verify keys failed for Pda: "));
+        assert!(s.contains(&m.actual.to_string()));
+        assert!(s.contains(&m.expected.to_string()));
+    }
+}
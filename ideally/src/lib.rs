@@ -0,0 +1,29 @@
+//! Core traits for Ideally programs.
+//!
+//! See the repo README for the general approach.
+
+mod accounts;
+mod entrypoint;
+mod error;
+mod overrides;
+mod pda;
+mod resolve;
+mod verify;
+
+pub use accounts::*;
+pub use error::*;
+pub use overrides::*;
+pub use pda::*;
+pub use resolve::*;
+pub use verify::*;
+
+#[cfg(feature = "derive")]
+pub use ideally_derive::{static_pda, Resolver};
+
+/// Account traits used by [`Resolver`]
+#[cfg(feature = "derive")]
+pub use solana_readonly_account;
+
+// re-export for use in macros
+#[doc(hidden)]
+pub use solana_program;
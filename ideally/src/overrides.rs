@@ -0,0 +1,322 @@
+use solana_program::pubkey::Pubkey;
+
+use crate::{KeyMismatch, Resolve, ResolveWithProgramId};
+
+/// Implemented for solores-generated `*Keys` structs to access keys by field.
+///
+/// Use [`crate::impl_verify_accounts`] to implement this for the generated types
+/// in the interface crate.
+pub trait KeyFields {
+    /// Identifies an account of the instruction
+    type Field: Copy;
+
+    fn key(&self, field: Self::Field) -> Pubkey;
+
+    /// The snake_case field name of the account
+    fn name(field: Self::Field) -> &'static str;
+}
+
+/// Returns the key pinned for the account field named `name`, if any
+pub fn pinned_key<K: KeyFields>(pinned: &[(K::Field, Pubkey)], name: &str) -> Option<Pubkey> {
+    pinned
+        .iter()
+        .find(|(field, _)| K::name(*field) == name)
+        .map(|(_, key)| *key)
+}
+
+/// A [`Resolve`]r that can use caller-pinned keys for its constrained accounts
+/// in place of resolving them, e.g. skipping reading a mint's owner if the token
+/// program is pinned. Accounts resolved from a pinned account use the pinned key.
+///
+/// Derived by [`crate::Resolver`].
+pub trait ResolvePinned: Resolve
+where
+    Self::Keys: KeyFields,
+{
+    /// The snake_case field names of the free accounts, which are inputs of
+    /// the resolver and so cannot be pinned
+    const FREE_ACCOUNTS: &'static [&'static str];
+
+    /// Same as [`Resolve::resolve`], but uses the pinned key, if any,
+    /// for accounts that can be resolved without the others,
+    /// and accounts that do not output [`Resolve::Data`]
+    fn resolve_pinned(
+        &self,
+        pinned: &[(<Self::Keys as KeyFields>::Field, Pubkey)],
+    ) -> Result<(Self::Keys, Self::Data), Self::Err>;
+}
+
+/// [`ResolvePinned`] for [`ResolveWithProgramId`] resolvers
+pub trait ResolvePinnedWithProgramId: ResolvePinned + ResolveWithProgramId
+where
+    Self::Keys: KeyFields,
+{
+    fn resolve_pinned_with_program_id(
+        &self,
+        pinned: &[(<Self::Keys as KeyFields>::Field, Pubkey)],
+        program_id: &Pubkey,
+    ) -> Result<(Self::Keys, Self::Data), Self::Err>;
+}
+
+/// A [`Resolve`]r with caller-pinned keys for some of the constrained accounts,
+/// e.g. when the user already knows the token program to use.
+///
+/// The pinned keys are fed into the resolution with [`ResolvePinned`],
+/// so pinning the token program skips reading the mint's owner.
+///
+/// Resolution fails with:
+/// - [`OverridesError::FreeAccount`] if a free account of the resolver is pinned.
+///   Set it on the resolver instead.
+/// - [`OverridesError::Conflict`] if a pinned key disagrees with the resolved one
+///   e.g. a pinned PDA that is not the derived address,
+///   so user-provided and resolved keys can be merged safely.
+///
+/// Example:
+///
+/// ```rust ignore
+/// let (keys, _) = WithOverrides {
+///     resolver: CreateRootAccounts { .. },
+///     overrides: &[(CreateAccountField::TokenProgram, spl_token::ID)],
+/// }
+/// .resolve()?;
+/// ```
+pub struct WithOverrides<'a, R: ResolvePinned>
+where
+    R::Keys: KeyFields,
+{
+    pub resolver: R,
+    pub overrides: &'a [(<R::Keys as KeyFields>::Field, Pubkey)],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverridesError<E, F> {
+    Resolve(E),
+
+    /// A free account was pinned
+    FreeAccount(F),
+
+    /// `actual` is the pinned key, `expected` is the resolved key
+    Conflict(KeyMismatch<F>),
+}
+
+impl<R: ResolvePinned> WithOverrides<'_, R>
+where
+    R::Keys: KeyFields,
+{
+    fn check_free(&self) -> Result<(), <Self as Resolve>::Err> {
+        match self
+            .overrides
+            .iter()
+            .find(|(field, _)| R::FREE_ACCOUNTS.contains(&R::Keys::name(*field)))
+        {
+            Some((field, _)) => Err(OverridesError::FreeAccount(*field)),
+            None => Ok(()),
+        }
+    }
+
+    fn check(
+        &self,
+        resolved: Result<(R::Keys, R::Data), R::Err>,
+    ) -> Result<(R::Keys, R::Data), <Self as Resolve>::Err> {
+        let (keys, data) = resolved.map_err(OverridesError::Resolve)?;
+        for (field, pinned) in self.overrides {
+            let resolved = keys.key(*field);
+            if *pinned != resolved {
+                return Err(OverridesError::Conflict(KeyMismatch {
+                    field: *field,
+                    actual: *pinned,
+                    expected: resolved,
+                }));
+            }
+        }
+        Ok((keys, data))
+    }
+}
+
+impl<R: ResolvePinned> Resolve for WithOverrides<'_, R>
+where
+    R::Keys: KeyFields,
+{
+    type Keys = R::Keys;
+
+    type Data = R::Data;
+
+    type Err = OverridesError<R::Err, <R::Keys as KeyFields>::Field>;
+
+    fn resolve(&self) -> Result<(Self::Keys, Self::Data), Self::Err> {
+        self.check_free()?;
+        self.check(self.resolver.resolve_pinned(self.overrides))
+    }
+}
+
+impl<R: ResolvePinnedWithProgramId> ResolveWithProgramId for WithOverrides<'_, R>
+where
+    R::Keys: KeyFields,
+{
+    fn resolve_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<(Self::Keys, Self::Data), Self::Err> {
+        self.check_free()?;
+        self.check(
+            self.resolver
+                .resolve_pinned_with_program_id(self.overrides, program_id),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Field {
+        Free,
+        Owner,
+        Constant,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Keys {
+        free: Pubkey,
+        owner: Pubkey,
+        constant: Pubkey,
+    }
+
+    impl KeyFields for Keys {
+        type Field = Field;
+
+        fn key(&self, field: Field) -> Pubkey {
+            match field {
+                Field::Free => self.free,
+                Field::Owner => self.owner,
+                Field::Constant => self.constant,
+            }
+        }
+
+        fn name(field: Field) -> &'static str {
+            match field {
+                Field::Free => "free",
+                Field::Owner => "owner",
+                Field::Constant => "constant",
+            }
+        }
+    }
+
+    const CONSTANT: Pubkey = Pubkey::new_from_array([1; 32]);
+
+    /// `owner` is read from the account, which is `None` if not fetched
+    struct Root {
+        free: Pubkey,
+        owner_of_free: Option<Pubkey>,
+    }
+
+    impl Resolve for Root {
+        type Keys = Keys;
+
+        type Data = ();
+
+        type Err = Infallible;
+
+        fn resolve(&self) -> Result<(Keys, ()), Infallible> {
+            self.resolve_pinned(&[])
+        }
+    }
+
+    impl ResolvePinned for Root {
+        const FREE_ACCOUNTS: &'static [&'static str] = &["free"];
+
+        fn resolve_pinned(&self, pinned: &[(Field, Pubkey)]) -> Result<(Keys, ()), Infallible> {
+            let owner = pinned_key::<Keys>(pinned, "owner")
+                .unwrap_or_else(|| self.owner_of_free.expect("owner read"));
+            Ok((
+                Keys {
+                    free: self.free,
+                    owner,
+                    constant: CONSTANT,
+                },
+                (),
+            ))
+        }
+    }
+
+    #[test]
+    fn pinned_owner_skips_resolution() {
+        let free = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (keys, ()) = WithOverrides {
+            resolver: Root {
+                free,
+                owner_of_free: None,
+            },
+            overrides: &[(Field::Owner, owner)],
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(
+            keys,
+            Keys {
+                free,
+                owner,
+                constant: CONSTANT
+            }
+        );
+    }
+
+    #[test]
+    fn pinned_agrees() {
+        let free = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let resolver = Root {
+            free,
+            owner_of_free: Some(owner),
+        };
+        let expected = resolver.resolve().unwrap();
+        let res = WithOverrides {
+            resolver,
+            overrides: &[(Field::Constant, CONSTANT)],
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn pinned_conflicts() {
+        let pinned = Pubkey::new_unique();
+        let err = WithOverrides {
+            resolver: Root {
+                free: Pubkey::new_unique(),
+                owner_of_free: Some(Pubkey::new_unique()),
+            },
+            overrides: &[(Field::Constant, pinned)],
+        }
+        .resolve()
+        .unwrap_err();
+        assert_eq!(
+            err,
+            OverridesError::Conflict(KeyMismatch {
+                field: Field::Constant,
+                actual: pinned,
+                expected: CONSTANT,
+            })
+        );
+    }
+
+    #[test]
+    fn pinned_free_account_rejected() {
+        let free = Pubkey::new_unique();
+        let err = WithOverrides {
+            resolver: Root {
+                free,
+                owner_of_free: Some(Pubkey::new_unique()),
+            },
+            overrides: &[(Field::Free, free)],
+        }
+        .resolve()
+        .unwrap_err();
+        assert_eq!(err, OverridesError::FreeAccount(Field::Free));
+    }
+}
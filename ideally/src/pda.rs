@@ -0,0 +1,410 @@
+use solana_program::{
+    hash::hashv,
+    pubkey::{Pubkey, PubkeyError, MAX_SEEDS, MAX_SEED_LEN},
+};
+
+/// Suffix of the hashed bytes of every PDA, same as `Pubkey::create_program_address()`'s
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Args to find a PDA with, i.e. its seeds without the bump
+pub trait FindPda: Sized {
+    /// Args to create the PDA with, i.e. its seeds with the found bump,
+    /// e.g. for signing CPIs
+    type CreateArgs;
+
+    /// ID of the canonical deployment of the program the PDA belongs to
+    const PROGRAM_ID: Pubkey;
+
+    /// Returns the PDA found for the program deployed at `program_id`
+    /// and the args to create it with
+    fn find_pda_with_program_id(self, program_id: &Pubkey) -> (Pubkey, Self::CreateArgs);
+
+    /// Returns the PDA found for [`Self::PROGRAM_ID`] and the args to create it with
+    fn find_pda(self) -> (Pubkey, Self::CreateArgs) {
+        self.find_pda_with_program_id(&Self::PROGRAM_ID)
+    }
+}
+
+/// A single PDA seed that can be converted to bytes without allocating.
+///
+/// Integers are encoded little-endian.
+pub trait Seed {
+    type Bytes<'a>: AsRef<[u8]>
+    where
+        Self: 'a;
+
+    fn seed_bytes(&self) -> Self::Bytes<'_>;
+}
+
+impl Seed for Pubkey {
+    type Bytes<'a> = &'a [u8];
+
+    fn seed_bytes(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+impl<const N: usize> Seed for [u8; N] {
+    type Bytes<'a> = &'a [u8];
+
+    fn seed_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+macro_rules! impl_seed_int {
+    ($($int:ty),+) => {
+        $(
+            impl Seed for $int {
+                type Bytes<'a> = [u8; core::mem::size_of::<$int>()];
+
+                fn seed_bytes(&self) -> Self::Bytes<'_> {
+                    self.to_le_bytes()
+                }
+            }
+        )+
+    };
+}
+
+impl_seed_int!(u8, u16, u32, u64, u128);
+
+/// The seeds of a PDA, excluding the bump.
+///
+/// Seeds are passed to closures instead of returned so that
+/// seeds that do not impl `AsRef<[u8]>`, like integers,
+/// can be encoded on the stack.
+///
+/// Usually implemented with [`crate::pda_seeds`]
+pub trait PdaSeeds {
+    /// Calls `f` with the seeds
+    fn with_seeds<R>(&self, f: impl FnOnce(&[&[u8]]) -> R) -> R;
+
+    /// Calls `f` with the seeds followed by `bump`, e.g. for `invoke_signed()`
+    fn with_signer_seeds<R>(&self, bump: u8, f: impl FnOnce(&[&[u8]]) -> R) -> R;
+
+    fn find_program_address(&self, program_id: &Pubkey) -> (Pubkey, u8) {
+        self.with_seeds(|seeds| Pubkey::find_program_address(seeds, program_id))
+    }
+
+    fn create_program_address(&self, bump: u8, program_id: &Pubkey) -> Result<Pubkey, PubkeyError> {
+        self.with_signer_seeds(bump, |seeds| {
+            Pubkey::create_program_address(seeds, program_id)
+        })
+    }
+
+    /// [`Self::create_program_address`] for a caller-supplied bump that must be
+    /// the canonical one returned by [`Self::find_program_address`].
+    ///
+    /// Returns [`PubkeyError::InvalidSeeds`] if `bump` is not valid or any bump above it is.
+    ///
+    /// Every bump from 255 down to `bump` is still checked, but with a sha256 and
+    /// a curve25519 point validation syscall each instead of `create_program_address`'s
+    /// costlier syscall, so this costs fewer compute units than [`Self::find_program_address`].
+    /// On-chain, requires the curve25519 syscalls to be enabled.
+    fn create_canonical_program_address(
+        &self,
+        bump: u8,
+        program_id: &Pubkey,
+    ) -> Result<Pubkey, PubkeyError> {
+        self.with_seeds(|seeds| {
+            // bump seed takes up 1
+            if seeds.len() >= MAX_SEEDS || seeds.iter().any(|s| s.len() > MAX_SEED_LEN) {
+                return Err(PubkeyError::MaxSeedLengthExceeded);
+            }
+            for candidate in (bump..=u8::MAX).rev() {
+                let bump_seed = [candidate];
+                // seeds, bump, program_id, PDA_MARKER
+                let mut parts: [&[u8]; MAX_SEEDS + 2] = [&[]; MAX_SEEDS + 2];
+                parts[..seeds.len()].copy_from_slice(seeds);
+                parts[seeds.len()] = &bump_seed;
+                parts[seeds.len() + 1] = program_id.as_ref();
+                parts[seeds.len() + 2] = PDA_MARKER;
+                let address = hashv(&parts[..seeds.len() + 3]).to_bytes();
+                if !is_on_curve(&address) {
+                    return if candidate == bump {
+                        Ok(Pubkey::new_from_array(address))
+                    } else {
+                        Err(PubkeyError::InvalidSeeds)
+                    };
+                }
+            }
+            Err(PubkeyError::InvalidSeeds)
+        })
+    }
+}
+
+/// `Pubkey::is_on_curve()` is unimplemented on-chain, so use the curve25519 syscall there
+fn is_on_curve(bytes: &[u8; 32]) -> bool {
+    #[cfg(target_os = "solana")]
+    {
+        const CURVE25519_EDWARDS: u64 = 0;
+        let mut result = 0u8;
+        // returns 0 if the point is valid
+        unsafe {
+            solana_program::syscalls::sol_curve_validate_point(
+                CURVE25519_EDWARDS,
+                bytes.as_ptr(),
+                &mut result,
+            ) == 0
+        }
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        Pubkey::new_from_array(*bytes).is_on_curve()
+    }
+}
+
+/// [`FindPda::CreateArgs`] of [`crate::pda_seeds`] PDAs:
+/// the seeds and found bump
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PdaCreateArgs<S> {
+    pub find: S,
+    pub bump: u8,
+}
+
+impl<S: PdaSeeds> PdaCreateArgs<S> {
+    /// Calls `f` with the seeds followed by the bump, e.g. for `invoke_signed()`
+    pub fn with_signer_seeds<R>(&self, f: impl FnOnce(&[&[u8]]) -> R) -> R {
+        self.find.with_signer_seeds(self.bump, f)
+    }
+}
+
+/// Defines a struct of PDA seeds and implements [`PdaSeeds`] for it.
+///
+/// Seeds are listed in order and are either literals, which must impl
+/// `AsRef<[u8]>` e.g. `"vault"` or `b"vault"`, or struct fields whose type
+/// impls [`Seed`].
+///
+/// If `program_id = ...` is provided, also implements [`FindPda`] with
+/// [`PdaCreateArgs`] for the struct so that it can be used with the
+/// `Resolver` derive. The program ID must be a const expression.
+///
+/// Example:
+///
+/// ```rust ignore
+/// ideally::pda_seeds! {
+///     /// The `index`-th vault of `authority`
+///     #[derive(Clone, Copy, Debug)]
+///     pub struct VaultFindPdaArgs(program_id = crate::ID) {
+///         "vault",
+///         authority: Pubkey,
+///         index: u64,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! pda_seeds {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident $((program_id = $program_id:expr))? {
+            $($seeds:tt)*
+        }
+    ) => {
+        $crate::pda_seeds!(
+            @munch [$(#[$meta])* $vis $name $(($program_id))?] [] [] $($seeds)*
+        );
+    };
+    (@munch $head:tt [$($fields:tt)*] [$($seed:tt)*] $lit:literal $(, $($rest:tt)*)?) => {
+        $crate::pda_seeds!(@munch $head [$($fields)*] [$($seed)* (lit $lit)] $($($rest)*)?);
+    };
+    (
+        @munch $head:tt [$($fields:tt)*] [$($seed:tt)*]
+        $(#[$field_meta:meta])* $field:ident: $ty:ty $(, $($rest:tt)*)?
+    ) => {
+        $crate::pda_seeds!(
+            @munch $head
+            [$($fields)* $(#[$field_meta])* pub $field: $ty,]
+            [$($seed)* (field $field)]
+            $($($rest)*)?
+        );
+    };
+    (
+        @munch [$(#[$meta:meta])* $vis:vis $name:ident $(($program_id:expr))?]
+        [$($fields:tt)*] [$(($kind:ident $seed:tt))*]
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($fields)*
+        }
+
+        impl $crate::PdaSeeds for $name {
+            fn with_seeds<R>(&self, f: impl FnOnce(&[&[u8]]) -> R) -> R {
+                f(&[$($crate::pda_seeds!(@seed self $kind $seed)),*])
+            }
+
+            fn with_signer_seeds<R>(&self, bump: u8, f: impl FnOnce(&[&[u8]]) -> R) -> R {
+                f(&[
+                    $($crate::pda_seeds!(@seed self $kind $seed),)*
+                    ::core::slice::from_ref(&bump),
+                ])
+            }
+        }
+
+        $(
+            impl $crate::FindPda for $name {
+                type CreateArgs = $crate::PdaCreateArgs<Self>;
+
+                const PROGRAM_ID: $crate::solana_program::pubkey::Pubkey = $program_id;
+
+                fn find_pda_with_program_id(
+                    self,
+                    program_id: &$crate::solana_program::pubkey::Pubkey,
+                ) -> (
+                    $crate::solana_program::pubkey::Pubkey,
+                    $crate::PdaCreateArgs<Self>,
+                ) {
+                    let (pda, bump) = $crate::PdaSeeds::find_program_address(&self, program_id);
+                    (pda, $crate::PdaCreateArgs { find: self, bump })
+                }
+            }
+        )?
+    };
+    // `self` is passed in from the expansion that defines the methods for hygiene
+    (@seed $self_:tt lit $lit:literal) => {
+        ::core::convert::AsRef::<[u8]>::as_ref($lit)
+    };
+    (@seed $self_:tt field $field:ident) => {
+        ::core::convert::AsRef::<[u8]>::as_ref(&$crate::Seed::seed_bytes(&$self_.$field))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_ID: Pubkey = Pubkey::new_from_array([1; 32]);
+
+    crate::pda_seeds! {
+        /// literals, fields with attributes and every [`Seed`] impl, no trailing comma
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct MixedFindPdaArgs(program_id = PROGRAM_ID) {
+            "str",
+            b"bytes",
+            #[allow(dead_code)]
+            authority: Pubkey,
+            index: u64,
+            small: u8,
+            wide: u128,
+            raw: [u8; 3]
+        }
+    }
+
+    crate::pda_seeds! {
+        pub struct LiteralsOnly {
+            b"global",
+        }
+    }
+
+    fn mixed() -> MixedFindPdaArgs {
+        MixedFindPdaArgs {
+            authority: Pubkey::new_unique(),
+            index: 0x0102_0304_0506_0708,
+            small: 9,
+            wide: 10,
+            raw: [11, 12, 13],
+        }
+    }
+
+    fn expected_seeds(args: &MixedFindPdaArgs) -> Vec<Vec<u8>> {
+        vec![
+            b"str".to_vec(),
+            b"bytes".to_vec(),
+            args.authority.to_bytes().to_vec(),
+            args.index.to_le_bytes().to_vec(),
+            vec![args.small],
+            args.wide.to_le_bytes().to_vec(),
+            args.raw.to_vec(),
+        ]
+    }
+
+    #[test]
+    fn seeds_in_order() {
+        let args = mixed();
+        let expected = expected_seeds(&args);
+        args.with_seeds(|seeds| assert_eq!(seeds, expected));
+        LiteralsOnly {}.with_seeds(|seeds| assert_eq!(seeds, [b"global"]));
+    }
+
+    #[test]
+    fn signer_seeds_end_with_bump() {
+        let args = mixed();
+        let mut expected = expected_seeds(&args);
+        expected.push(vec![254]);
+        args.with_signer_seeds(254, |seeds| assert_eq!(seeds, expected));
+    }
+
+    #[test]
+    fn find_pda_matches_find_program_address() {
+        let args = mixed();
+        let seeds = expected_seeds(&args);
+        let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (expected_pda, expected_bump) = Pubkey::find_program_address(&seeds, &PROGRAM_ID);
+
+        let (pda, create_args) = args.find_pda();
+        assert_eq!(pda, expected_pda);
+        assert_eq!(
+            create_args,
+            PdaCreateArgs {
+                find: args,
+                bump: expected_bump
+            }
+        );
+        create_args.with_signer_seeds(|signer_seeds| {
+            assert_eq!(
+                Pubkey::create_program_address(signer_seeds, &PROGRAM_ID),
+                Ok(expected_pda)
+            );
+        });
+        assert_eq!(
+            args.create_canonical_program_address(expected_bump, &PROGRAM_ID),
+            Ok(expected_pda)
+        );
+    }
+
+    #[test]
+    fn create_canonical_rejects_lower_bump() {
+        let args = mixed();
+        let (_, bump) = args.find_program_address(&PROGRAM_ID);
+        let lower = (0..bump)
+            .rev()
+            .find(|b| args.create_program_address(*b, &PROGRAM_ID).is_ok())
+            .expect("a valid non-canonical bump");
+        assert_eq!(
+            args.create_canonical_program_address(lower, &PROGRAM_ID),
+            Err(PubkeyError::InvalidSeeds)
+        );
+    }
+
+    #[test]
+    fn create_canonical_matches_find_program_address() {
+        for _ in 0..64 {
+            let args = mixed();
+            let (pda, bump) = args.find_program_address(&PROGRAM_ID);
+            assert_eq!(
+                args.create_canonical_program_address(bump, &PROGRAM_ID),
+                Ok(pda)
+            );
+            if let Some(higher) = bump.checked_add(1) {
+                // every bump above the canonical one is on curve
+                assert_eq!(
+                    args.create_canonical_program_address(higher, &PROGRAM_ID),
+                    Err(PubkeyError::InvalidSeeds)
+                );
+            }
+        }
+    }
+
+    crate::pda_seeds! {
+        pub struct TooManySeeds {
+            "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15",
+        }
+    }
+
+    #[test]
+    fn create_canonical_max_seeds() {
+        assert_eq!(
+            TooManySeeds {}.create_canonical_program_address(255, &PROGRAM_ID),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+    }
+}
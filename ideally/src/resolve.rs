@@ -0,0 +1,57 @@
+use core::convert::Infallible;
+
+use solana_program::pubkey::Pubkey;
+
+/// A set of accounts (and any other supporting data) that can be resolved
+/// into the full `*Keys` struct generated by solores for an instruction.
+///
+/// Typically implemented on the free-accounts struct of an instruction.
+/// For multi-step resolutions, implement it on every intermediate struct too
+/// so that callers can start from whichever step they have the data for.
+pub trait Resolve {
+    /// The solores-generated `*Keys` struct this resolves to
+    type Keys;
+
+    /// Supporting data computed as part of the resolution that the caller
+    /// might need later e.g. PDA signer seeds. Use `()` if there's none.
+    type Data;
+
+    /// Use [`Infallible`] if resolution can never fail
+    type Err;
+
+    fn resolve(&self) -> Result<(Self::Keys, Self::Data), Self::Err>;
+}
+
+/// A [`Resolve`]r whose PDAs can be derived for a deployment of the program
+/// at an address other than the canonical one, e.g. on a fork or localnet.
+///
+/// [`Resolve::resolve`] should be equivalent to calling
+/// [`Self::resolve_with_program_id`] with the canonical program ID.
+pub trait ResolveWithProgramId: Resolve {
+    fn resolve_with_program_id(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<(Self::Keys, Self::Data), Self::Err>;
+}
+
+/// Convenience for [`Resolve`]rs that can never fail
+pub trait ResolveInfallible: Resolve<Err = Infallible> {
+    fn resolve_infallible(&self) -> (Self::Keys, Self::Data) {
+        match self.resolve() {
+            Ok(res) => res,
+            Err(e) => match e {},
+        }
+    }
+
+    fn resolve_infallible_with_program_id(&self, program_id: &Pubkey) -> (Self::Keys, Self::Data)
+    where
+        Self: ResolveWithProgramId,
+    {
+        match self.resolve_with_program_id(program_id) {
+            Ok(res) => res,
+            Err(e) => match e {},
+        }
+    }
+}
+
+impl<R: Resolve<Err = Infallible> + ?Sized> ResolveInfallible for R {}
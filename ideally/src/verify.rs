@@ -0,0 +1,262 @@
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+
+/// Verification of a solores-generated `*Accounts` struct against
+/// the `*Keys` output by a [`crate::Resolve`]r.
+///
+/// Use [`impl_verify_accounts`] to implement this for the generated types
+/// in the interface crate.
+pub trait VerifyAccounts {
+    /// The solores-generated `*Keys` struct for the same instruction
+    type Keys;
+
+    /// Identifies an account of the instruction
+    type Field: Copy;
+
+    /// Returns the first mismatched account
+    fn verify_keys(&self, expected: &Self::Keys) -> Result<(), KeyMismatch<Self::Field>>;
+
+    /// Checks all writable and signer privileges
+    fn verify_privileges(&self) -> Result<(), ProgramError>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyMismatch<F> {
+    pub field: F,
+    pub actual: Pubkey,
+    pub expected: Pubkey,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyAccountsError<F> {
+    KeyMismatch(KeyMismatch<F>),
+    Privileges(ProgramError),
+}
+
+impl<F> From<VerifyAccountsError<F>> for ProgramError {
+    fn from(e: VerifyAccountsError<F>) -> Self {
+        match e {
+            VerifyAccountsError::KeyMismatch(_) => Self::InvalidAccountData,
+            VerifyAccountsError::Privileges(e) => e,
+        }
+    }
+}
+
+/// Verifies both the pubkeys and the privileges of `accounts`,
+/// in that order
+pub fn verify<A: VerifyAccounts + ?Sized>(
+    accounts: &A,
+    expected: &A::Keys,
+) -> Result<(), VerifyAccountsError<A::Field>> {
+    accounts
+        .verify_keys(expected)
+        .map_err(VerifyAccountsError::KeyMismatch)?;
+    accounts
+        .verify_privileges()
+        .map_err(VerifyAccountsError::Privileges)
+}
+
+/// The error to return and message to log when a specific account of an
+/// instruction does not match the expected key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMismatchRule<F> {
+    pub field: F,
+    pub err: ProgramError,
+    pub msg: Option<&'static str>,
+}
+
+/// Declarative table of the errors to return for [`KeyMismatch`]es of
+/// an instruction's accounts.
+///
+/// Example:
+///
+/// ```rust ignore
+/// pub const CREATE_KEY_MISMATCH_ERRS: KeyMismatchErrs<CreateAccountField> = KeyMismatchErrs {
+///     rules: &[KeyMismatchRule {
+///         field: CreateAccountField::AssociatedTokenAccount,
+///         err: ProgramError::InvalidSeeds,
+///         msg: Some("Error: Associated address does not match seed derivation"),
+///     }],
+///     default: ProgramError::InvalidAccountData,
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMismatchErrs<F: 'static> {
+    pub rules: &'static [KeyMismatchRule<F>],
+
+    /// Returned for accounts without a rule
+    pub default: ProgramError,
+}
+
+impl<F: PartialEq> KeyMismatchErrs<F> {
+    pub fn rule(&self, field: &F) -> Option<&KeyMismatchRule<F>> {
+        self.rules.iter().find(|rule| rule.field == *field)
+    }
+
+    /// The error for a mismatched `field`
+    pub fn program_error(&self, field: &F) -> ProgramError {
+        self.rule(field)
+            .map_or_else(|| self.default.clone(), |rule| rule.err.clone())
+    }
+
+    /// Logs the rule's msg, if any, and returns its error
+    pub fn to_program_error(&self, field: &F) -> ProgramError {
+        match self.rule(field) {
+            Some(KeyMismatchRule { err, msg, .. }) => {
+                if let Some(msg) = msg {
+                    msg!(msg);
+                }
+                err.clone()
+            }
+            None => self.default.clone(),
+        }
+    }
+}
+
+/// [`verify`], but with key mismatches converted to [`ProgramError`]s
+/// according to `errs`
+pub fn verify_with<A: VerifyAccounts + ?Sized>(
+    accounts: &A,
+    expected: &A::Keys,
+    errs: &KeyMismatchErrs<A::Field>,
+) -> Result<(), ProgramError>
+where
+    A::Field: PartialEq,
+{
+    verify(accounts, expected).map_err(|e| match e {
+        VerifyAccountsError::KeyMismatch(KeyMismatch { field, .. }) => {
+            errs.to_program_error(&field)
+        }
+        VerifyAccountsError::Privileges(e) => e,
+    })
+}
+
+/// Implements [`VerifyAccounts`] for a solores-generated `*Accounts` struct
+/// and [`crate::KeyFields`] for its `*Keys` struct, generating their shared
+/// `Field` enum from the given `Variant: struct_field` list.
+///
+/// Privileges are checked by delegating to the generated
+/// `*_verify_account_privileges` fn.
+///
+/// Every field of the `*Accounts` and `*Keys` structs must be listed,
+/// otherwise compilation fails:
+///
+/// ```compile_fail
+/// use ideally::solana_program::{
+///     account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+/// };
+///
+/// pub struct FooAccounts<'me, 'info> {
+///     pub a: &'me AccountInfo<'info>,
+///     pub b: &'me AccountInfo<'info>,
+/// }
+///
+/// pub struct FooKeys {
+///     pub a: Pubkey,
+///     pub b: Pubkey,
+/// }
+///
+/// fn foo_verify_account_privileges(_: &FooAccounts) -> Result<(), ProgramError> {
+///     Ok(())
+/// }
+///
+/// ideally::impl_verify_accounts!(
+///     FooAccounts,
+///     FooKeys,
+///     foo_verify_account_privileges,
+///     FooAccountField { A: a } // <-- b is not verified
+/// );
+/// ```
+///
+/// Example:
+///
+/// ```rust ignore
+/// ideally::impl_verify_accounts!(
+///     CreateAccounts,
+///     CreateKeys,
+///     create_verify_account_privileges,
+///     CreateAccountField {
+///         FundingAccount: funding_account,
+///         AssociatedTokenAccount: associated_token_account,
+///         Wallet: wallet,
+///         Mint: mint,
+///         SystemProgram: system_program,
+///         TokenProgram: token_program,
+///     }
+/// );
+/// ```
+#[macro_export]
+macro_rules! impl_verify_accounts {
+    (
+        $accounts:ident,
+        $keys:ident,
+        $verify_privileges:path,
+        $field:ident { $($variant:ident: $member:ident),+ $(,)? }
+    ) => {
+        #[doc = concat!("Identifies an account of [`", stringify!($accounts), "`]")]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $field {
+            $($variant),+
+        }
+
+        impl $field {
+            /// The snake_case field name of the account
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($member)),+
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for $field {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl $crate::KeyFields for $keys {
+            type Field = $field;
+
+            fn key(&self, field: $field) -> $crate::solana_program::pubkey::Pubkey {
+                match field {
+                    $($field::$variant => self.$member),+
+                }
+            }
+
+            fn name(field: $field) -> &'static str {
+                field.name()
+            }
+        }
+
+        impl $crate::VerifyAccounts for $accounts<'_, '_> {
+            type Keys = $keys;
+
+            type Field = $field;
+
+            fn verify_keys(
+                &self,
+                expected: &$keys,
+            ) -> ::core::result::Result<(), $crate::KeyMismatch<$field>> {
+                // exhaustive so that an account left out of the list is a compile error
+                let $accounts { $($member: _),+ } = self;
+                let $keys { $($member),+ } = expected;
+                $(
+                    if self.$member.key != $member {
+                        return Err($crate::KeyMismatch {
+                            field: $field::$variant,
+                            actual: *self.$member.key,
+                            expected: *$member,
+                        });
+                    }
+                )+
+                Ok(())
+            }
+
+            fn verify_privileges(
+                &self,
+            ) -> ::core::result::Result<(), $crate::solana_program::program_error::ProgramError>
+            {
+                $verify_privileges(self)
+            }
+        }
+    };
+}
@@ -0,0 +1,127 @@
+//! The subset of the shank-style IDL that's needed for scaffolding
+
+use heck::{ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct Idl {
+    pub name: String,
+    pub instructions: Vec<IdlInstruction>,
+    #[serde(default)]
+    pub pdas: Vec<IdlPda>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub accounts: Vec<IdlAccount>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlAccount {
+    pub name: String,
+    #[serde(default)]
+    pub is_free: bool,
+}
+
+/// Not part of shank: seed schema of a PDA, from which
+/// `*FindPdaArgs` and `*CreatePdaArgs` are generated
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlPda {
+    pub name: String,
+
+    /// Base58 ID of the program that derives the PDA. Defaults to this program
+    #[serde(default)]
+    pub program_id: Option<String>,
+
+    pub seeds: Vec<IdlSeed>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum IdlSeed {
+    /// A constant string seed
+    Const { value: String },
+
+    /// The pubkey of an account
+    Account { name: String },
+
+    /// An instruction arg or other value
+    Arg {
+        name: String,
+        #[serde(rename = "type")]
+        ty: String,
+    },
+}
+
+impl IdlInstruction {
+    /// e.g. `CreateIdempotent`, as used in solores-generated type names
+    pub fn type_name(&self) -> String {
+        self.name.to_upper_camel_case()
+    }
+
+    /// e.g. `create_idempotent`, as used in solores-generated fn names
+    pub fn fn_name(&self) -> String {
+        self.name.to_snake_case()
+    }
+
+    /// e.g. `CREATE_IDEMPOTENT`, as used in solores-generated consts
+    pub fn const_name(&self) -> String {
+        self.name.to_shouty_snake_case()
+    }
+
+    /// Accounts with `isFree: true`.
+    /// If none of the instruction's accounts are marked, all of them are free.
+    pub fn free_accounts(&self) -> Vec<&IdlAccount> {
+        if self.accounts.iter().any(|a| a.is_free) {
+            self.accounts.iter().filter(|a| a.is_free).collect()
+        } else {
+            self.accounts.iter().collect()
+        }
+    }
+
+    pub fn constrained_accounts(&self) -> Vec<&IdlAccount> {
+        let free = self.free_accounts();
+        self.accounts
+            .iter()
+            .filter(|a| !free.iter().any(|f| f.name == a.name))
+            .collect()
+    }
+}
+
+impl IdlAccount {
+    /// e.g. `funding_account`, as used in solores-generated struct fields
+    pub fn field_name(&self) -> String {
+        self.name.to_snake_case()
+    }
+
+    /// e.g. `FundingAccount`, as used in `ideally::impl_verify_accounts!()` field enums
+    pub fn variant_name(&self) -> String {
+        self.name.to_upper_camel_case()
+    }
+}
+
+impl IdlSeed {
+    /// Whether the seed is a `Pubkey` field
+    pub fn is_pubkey(&self) -> bool {
+        match self {
+            Self::Const { .. } => false,
+            Self::Account { .. } => true,
+            Self::Arg { ty, .. } => ty == "publicKey",
+        }
+    }
+}
+
+impl IdlPda {
+    /// e.g. `AtaFindPdaArgs`
+    pub fn find_args_name(&self) -> String {
+        format!("{}FindPdaArgs", self.name.to_upper_camel_case())
+    }
+
+    /// e.g. `AtaCreatePdaArgs`
+    pub fn create_args_name(&self) -> String {
+        format!("{}CreatePdaArgs", self.name.to_upper_camel_case())
+    }
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use clap::{Parser, Subcommand};
+
+mod idl;
+mod new;
+mod pdas;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Args {
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    New(new::NewArgs),
+    Pdas(pdas::PdasArgs),
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    match args.cmd {
+        Cmd::New(args) => new::run(args),
+        Cmd::Pdas(args) => pdas::run(args),
+    }
+}
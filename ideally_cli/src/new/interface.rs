@@ -0,0 +1,66 @@
+use std::{error::Error, fs, path::Path};
+
+use crate::idl::Idl;
+
+use super::{write_file, Names};
+
+/// Adds `src/verify.rs` and the `ideally` dependency to the solores-generated interface crate
+pub fn extend(out: &Path, idl: &Idl, names: &Names) -> Result<(), Box<dyn Error>> {
+    let dir = out.join(&names.interface);
+
+    let cargo_toml_path = dir.join("Cargo.toml");
+    let cargo_toml = fs::read_to_string(&cargo_toml_path)?;
+    let cargo_toml = cargo_toml.replacen(
+        "[dependencies]\n",
+        &format!("[dependencies]\n{}\n", names.ideally_dep(&[])),
+        1,
+    );
+    fs::write(cargo_toml_path, cargo_toml)?;
+
+    let lib_rs_path = dir.join("src/lib.rs");
+    let mut lib_rs = fs::read_to_string(&lib_rs_path)?;
+    lib_rs.push_str("pub mod verify;\npub use verify::*;\n");
+    fs::write(lib_rs_path, lib_rs)?;
+
+    write_file(&dir, "src/verify.rs", &verify_rs(idl))
+}
+
+fn verify_rs(idl: &Idl) -> String {
+    let mut res = String::from(
+        "//! Not generated by solores: [`ideally`] trait impls for the generated types\n\nuse crate::*;\n",
+    );
+    for ix in idl.instructions.iter() {
+        let ty = ix.type_name();
+        let fields: String = ix
+            .accounts
+            .iter()
+            .map(|a| format!("        {}: {},\n", a.variant_name(), a.field_name()))
+            .collect();
+        let free: String = ix
+            .free_accounts()
+            .iter()
+            .map(|a| format!("        {},\n", a.field_name()))
+            .collect();
+        res.push_str(&format!(
+            r#"
+ideally::impl_verify_accounts!(
+    {ty}Accounts,
+    {ty}Keys,
+    {fn_name}_verify_account_privileges,
+    {ty}AccountField {{
+{fields}    }}
+);
+
+ideally::free_accounts!(
+    {ty}Accounts,
+    {const_name}_IX_ACCOUNTS_LEN,
+    {ty}FreeAccounts {{
+{free}    }}
+);
+"#,
+            fn_name = ix.fn_name(),
+            const_name = ix.const_name(),
+        ));
+    }
+    res
+}
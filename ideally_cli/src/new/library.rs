@@ -0,0 +1,127 @@
+use std::{error::Error, path::Path};
+
+use crate::{
+    idl::{Idl, IdlInstruction},
+    pdas::pdas_rs,
+};
+
+use super::{write_file, Names};
+
+pub fn write(out: &Path, idl: &Idl, names: &Names) -> Result<(), Box<dyn Error>> {
+    let dir = out.join(&names.library);
+    write_file(&dir, "Cargo.toml", &cargo_toml(names))?;
+    if idl.pdas.is_empty() {
+        write_file(&dir, "src/lib.rs", "pub mod resolvers;\n")?;
+    } else {
+        write_file(&dir, "src/lib.rs", "pub mod pda;\npub mod resolvers;\n")?;
+        write_file(&dir, "src/pda.rs", &pdas_rs(idl, &names.interface)?)?;
+    }
+    let mods: String = idl
+        .instructions
+        .iter()
+        .map(|ix| format!("pub mod {};\n", ix.fn_name()))
+        .collect();
+    write_file(&dir, "src/resolvers/mod.rs", &mods)?;
+    for ix in idl.instructions.iter() {
+        write_file(
+            &dir,
+            &format!("src/resolvers/{}.rs", ix.fn_name()),
+            &resolver_rs(ix, names),
+        )?;
+    }
+    Ok(())
+}
+
+fn cargo_toml(names: &Names) -> String {
+    format!(
+        r#"[package]
+name = "{library}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+{ideally}
+solana-program = "^1.16"
+{interface} = {{ path = "../{interface}" }}
+"#,
+        library = names.library,
+        ideally = names.ideally_dep(&["derive"]),
+        interface = names.interface,
+    )
+}
+
+fn resolver_rs(ix: &IdlInstruction, names: &Names) -> String {
+    let ty = ix.type_name();
+    let free = ix.free_accounts();
+    let constrained = ix.constrained_accounts();
+
+    let free_fields: String = free
+        .iter()
+        .map(|a| format!("    pub {}: Pubkey,\n", a.field_name()))
+        .collect();
+    let free_destructure: String = free
+        .iter()
+        .map(|a| format!("            {},\n", a.field_name()))
+        .collect();
+    let free_init: String = free
+        .iter()
+        .map(|a| format!("            {0}: *{0}.key,\n", a.field_name()))
+        .collect();
+    let resolve_body = if constrained.is_empty() {
+        let keys_init: String = free
+            .iter()
+            .map(|a| format!("                {0}: self.{0},\n", a.field_name()))
+            .collect();
+        format!("        Ok((\n            {ty}Keys {{\n{keys_init}            }},\n            (),\n        ))\n")
+    } else {
+        let names: Vec<String> = constrained.iter().map(|a| a.field_name()).collect();
+        format!(
+            "        // TODO: resolve the constrained accounts: {}\n        todo!()\n",
+            names.join(", ")
+        )
+    };
+
+    format!(
+        r#"use std::convert::Infallible;
+
+use ideally::{{KeyMismatchErrs, Resolve}};
+use solana_program::{{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey}};
+use {interface}::{{{ty}AccountField, {ty}FreeAccounts, {ty}Keys}};
+
+/// Errors to return for mismatched `{ix_name}` accounts
+pub const {const_name}_KEY_MISMATCH_ERRS: KeyMismatchErrs<{ty}AccountField> = KeyMismatchErrs {{
+    rules: &[],
+    default: ProgramError::InvalidAccountData,
+}};
+
+pub struct {ty}RootAccounts {{
+{free_fields}}}
+
+impl {ty}RootAccounts {{
+    /// Extracts the free accounts from the `{ix_name}` instruction's accounts.
+    ///
+    /// Returns [`ProgramError::NotEnoughAccountKeys`] if there are not enough accounts
+    pub fn from_account_infos(accounts: &[AccountInfo]) -> Result<Self, ProgramError> {{
+        let {ty}FreeAccounts {{
+{free_destructure}        }} = {ty}FreeAccounts::from_account_infos(accounts)?;
+        Ok(Self {{
+{free_init}        }})
+    }}
+}}
+
+impl Resolve for {ty}RootAccounts {{
+    type Keys = {ty}Keys;
+
+    type Data = ();
+
+    type Err = Infallible;
+
+    fn resolve(&self) -> Result<({ty}Keys, ()), Infallible> {{
+{resolve_body}    }}
+}}
+"#,
+        interface = names.interface,
+        const_name = ix.const_name(),
+        ix_name = ix.name,
+    )
+}
@@ -0,0 +1,210 @@
+//! `ideally new`: scaffolds the interface, library, on-chain program and
+//! program-test crate workspace of a new Ideally program from its IDL
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use clap::Args;
+use heck::{ToKebabCase, ToSnakeCase, ToUpperCamelCase};
+
+use crate::idl::Idl;
+
+mod interface;
+mod library;
+mod program;
+mod program_test;
+
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    /// Path to the program's handwritten idl.json
+    pub idl: PathBuf,
+
+    /// Directory to create the workspace in. Defaults to the IDL's program name
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Path to a local ideally crate to patch the crates.io version with
+    #[arg(long)]
+    pub ideally_path: Option<PathBuf>,
+
+    /// solores binary to generate the interface crate with,
+    /// called as `<solores> -o <out> -n <interface crate name> <idl>`
+    #[arg(long, default_value = "solores")]
+    pub solores: PathBuf,
+}
+
+/// Names of the generated crates and types
+pub struct Names {
+    /// e.g. `spl_associated_token_account`
+    pub program: String,
+
+    /// e.g. `spl_associated_token_account_interface`
+    pub interface: String,
+
+    /// e.g. `spl_associated_token_account_lib`
+    pub library: String,
+
+    /// e.g. `spl-associated-token-account-test`
+    pub program_test: String,
+
+    /// e.g. `SplAssociatedTokenAccountProgramIx`
+    pub program_ix: String,
+}
+
+/// `ideally` dependency of the generated Cargo.tomls.
+/// `--ideally-path` patches it in the workspace Cargo.toml.
+const IDEALLY_DEP: &str = "ideally = { version = \"^0.1\"";
+
+impl Names {
+    pub fn new(idl: &Idl) -> Self {
+        let program = idl.name.to_snake_case();
+        Self {
+            interface: format!("{program}_interface"),
+            library: format!("{program}_lib"),
+            program_test: format!("{}-test", program.to_kebab_case()),
+            program_ix: format!("{}ProgramIx", program.to_upper_camel_case()),
+            program,
+        }
+    }
+
+    pub fn ideally_dep(&self, features: &[&str]) -> String {
+        if features.is_empty() {
+            return format!("{IDEALLY_DEP} }}");
+        }
+        let features: Vec<String> = features.iter().map(|f| format!("\"{f}\"")).collect();
+        format!("{IDEALLY_DEP}, features = [{}] }}", features.join(", "))
+    }
+}
+
+/// Writes `contents` to `dir/path`, creating parent dirs as required
+pub fn write_file(dir: &Path, path: &str, contents: &str) -> Result<(), Box<dyn Error>> {
+    let path = dir.join(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn run(
+    NewArgs {
+        idl: idl_path,
+        out,
+        ideally_path,
+        solores,
+    }: NewArgs,
+) -> Result<(), Box<dyn Error>> {
+    let idl_str = fs::read_to_string(&idl_path)?;
+    let idl: Idl = serde_json::from_str(&idl_str)?;
+    let ideally_path = ideally_path.map(fs::canonicalize).transpose()?;
+    let names = Names::new(&idl);
+
+    let out = out.unwrap_or_else(|| PathBuf::from(&names.program));
+    if out.exists() && fs::read_dir(&out)?.next().is_some() {
+        return Err(format!("{} already exists and is not empty", out.display()).into());
+    }
+    fs::create_dir_all(&out)?;
+
+    write_file(&out, "idl.json", &idl_str)?;
+    write_file(
+        &out,
+        "Cargo.toml",
+        &workspace_cargo_toml(&names, ideally_path.as_deref()),
+    )?;
+    write_file(&out, "README.md", &readme(&names))?;
+
+    let status = Command::new(&solores)
+        .arg("-o")
+        .arg(&out)
+        .arg("-n")
+        .arg(&names.interface)
+        .arg(&idl_path)
+        .status()
+        .map_err(|e| {
+            format!(
+                "failed to run {}: {e}. Install it with `cargo install solores`",
+                solores.display()
+            )
+        })?;
+    if !status.success() {
+        return Err(format!("{} exited with {status}", solores.display()).into());
+    }
+    interface::extend(&out, &idl, &names)?;
+    library::write(&out, &idl, &names)?;
+    program::write(&out, &idl, &names)?;
+    program_test::write(&out, &idl, &names)?;
+
+    // formatting the generated code is best-effort
+    let _ = Command::new("cargo")
+        .arg("fmt")
+        .arg("--all")
+        .current_dir(&out)
+        .status();
+
+    eprintln!("Created {}", out.display());
+    Ok(())
+}
+
+fn workspace_cargo_toml(names: &Names, ideally_path: Option<&Path>) -> String {
+    let ideally_patch = match ideally_path {
+        Some(path) => format!("ideally = {{ path = \"{}\" }}\n", path.display()),
+        None => String::new(),
+    };
+    format!(
+        r#"[workspace]
+
+members = [
+    "program-test",
+    "{program}",
+    "{interface}",
+    "{library}"
+]
+
+# Point dependencies to local or unpublished versions here
+[patch.crates-io]
+{ideally_patch}"#,
+        program = names.program,
+        interface = names.interface,
+        library = names.library,
+    )
+}
+
+fn readme(names: &Names) -> String {
+    format!(
+        r#"# {program}
+
+Ideally program scaffolded with `ideally new`.
+
+## IDL
+
+`idl.json` is the handwritten shank-style IDL. Mark the free accounts of each instruction with `"isFree": true`.
+
+## Codegen
+
+`{interface}` crate generated using solores.
+
+`{interface}/src/verify.rs` is generated by `ideally new` and implements the `ideally` traits for the generated types. Make sure to add it back to `lib.rs` and the `ideally` dependency back to `Cargo.toml` when regenerating.
+
+## Library
+
+`{library}` contains one resolver per instruction in `src/resolvers/`. Implement the resolution of the constrained accounts there.
+
+## Program
+
+`{program}` is the on-chain program. Instruction dispatch and account verification is done, implement the rest of the instruction processors in `src/processor.rs`.
+
+## Tests
+
+Run the tests with `cd program-test && cargo test-sbf`.
+
+**MUST** manually rebuild program with `cargo build-sbf` before running `program-tests` on every modification.
+"#,
+        program = names.program,
+        interface = names.interface,
+        library = names.library,
+    )
+}
@@ -0,0 +1,118 @@
+use std::{error::Error, path::Path};
+
+use crate::idl::{Idl, IdlInstruction};
+
+use super::{write_file, Names};
+
+pub fn write(out: &Path, idl: &Idl, names: &Names) -> Result<(), Box<dyn Error>> {
+    let dir = out.join(&names.program);
+    write_file(&dir, "Cargo.toml", &cargo_toml(names))?;
+    write_file(&dir, "Xargo.toml", XARGO_TOML)?;
+    write_file(&dir, "src/lib.rs", &lib_rs(names))?;
+    write_file(&dir, "src/processor.rs", &processor_rs(idl, names))
+}
+
+const XARGO_TOML: &str = r#"[target.bpfel-unknown-unknown.dependencies.std]
+features = []
+"#;
+
+fn cargo_toml(names: &Names) -> String {
+    format!(
+        r#"[package]
+name = "{program}"
+version = "0.1.0"
+edition = "2021"
+
+[features]
+no-entrypoint = []
+test-sbf = []
+
+[dependencies]
+{ideally}
+solana-program = "^1.16"
+{interface} = {{ path = "../{interface}" }}
+{library} = {{ path = "../{library}" }}
+
+[lib]
+crate-type = ["cdylib", "lib"]
+"#,
+        program = names.program,
+        ideally = names.ideally_dep(&[]),
+        interface = names.interface,
+        library = names.library,
+    )
+}
+
+fn lib_rs(names: &Names) -> String {
+    format!(
+        r#"#![forbid(unsafe_code)]
+
+use solana_program::pubkey::Pubkey;
+
+pub mod processor;
+
+pub const ID: Pubkey = {interface}::ID;
+"#,
+        interface = names.interface,
+    )
+}
+
+fn processor_rs(idl: &Idl, names: &Names) -> String {
+    let mut interface_imports = vec![names.program_ix.clone()];
+    let mut library_imports = Vec::new();
+    let mut match_arms = String::new();
+    let mut processors = String::new();
+    for ix in idl.instructions.iter() {
+        let ty = ix.type_name();
+        let fn_name = ix.fn_name();
+        interface_imports.push(format!("{ty}Accounts"));
+        interface_imports.push(format!("{ty}IxArgs"));
+        library_imports.push(format!(
+            "{fn_name}::{{{ty}RootAccounts, {}_KEY_MISMATCH_ERRS}}",
+            ix.const_name()
+        ));
+        match_arms.push_str(&format!(
+            "        {ty}(args) => process_{fn_name}(accounts, args),\n"
+        ));
+        processors.push_str(&processor_fn(ix));
+    }
+    interface_imports.sort();
+
+    format!(
+        r#"use ideally::{{accounts_array, verify_with, ResolveInfallible}};
+use solana_program::{{account_info::AccountInfo, entrypoint::ProgramResult}};
+use {interface}::{{{interface_imports}}};
+use {library}::resolvers::{{{library_imports}}};
+
+ideally::program_entrypoint!(
+    crate::ID,
+    {program_ix},
+    |accounts| {{
+{match_arms}    }},
+);
+{processors}"#,
+        interface = names.interface,
+        interface_imports = interface_imports.join(", "),
+        library = names.library,
+        library_imports = library_imports.join(", "),
+        program_ix = names.program_ix,
+    )
+}
+
+fn processor_fn(ix: &IdlInstruction) -> String {
+    format!(
+        r#"
+fn process_{fn_name}(accounts: &[AccountInfo], _args: {ty}IxArgs) -> ProgramResult {{
+    let (expected_keys, _) = {ty}RootAccounts::from_account_infos(accounts)?.resolve_infallible();
+    let {fn_name}_accounts: {ty}Accounts = accounts_array(accounts)?.into();
+
+    verify_with(&{fn_name}_accounts, &expected_keys, &{const_name}_KEY_MISMATCH_ERRS)?;
+
+    todo!()
+}}
+"#,
+        fn_name = ix.fn_name(),
+        ty = ix.type_name(),
+        const_name = ix.const_name(),
+    )
+}
@@ -0,0 +1,70 @@
+use std::{error::Error, path::Path};
+
+use crate::idl::Idl;
+
+use super::{write_file, Names};
+
+pub fn write(out: &Path, idl: &Idl, names: &Names) -> Result<(), Box<dyn Error>> {
+    let dir = out.join("program-test");
+    write_file(&dir, "Cargo.toml", &cargo_toml(names))?;
+    write_file(&dir, "src/lib.rs", "//! All files in tests/\n")?;
+    write_file(&dir, "tests/program_test.rs", &program_test_rs(names))?;
+    for ix in idl.instructions.iter() {
+        write_file(
+            &dir,
+            &format!("tests/{}.rs", ix.fn_name()),
+            &ix_test_rs(&ix.fn_name()),
+        )?;
+    }
+    Ok(())
+}
+
+fn cargo_toml(names: &Names) -> String {
+    format!(
+        r#"[package]
+edition = "2021"
+name = "{program_test}"
+version = "0.0.0"
+
+[features]
+test-sbf = []
+
+[dev-dependencies]
+solana-program = "^1.16"
+solana-program-test = "^1.16"
+solana-sdk = "^1.16"
+{program} = {{ path = "../{program}", features = ["no-entrypoint"] }}
+"#,
+        program_test = names.program_test,
+        program = names.program,
+    )
+}
+
+fn program_test_rs(names: &Names) -> String {
+    format!(
+        r#"use solana_program_test::ProgramTest;
+
+pub fn program_test() -> ProgramTest {{
+    ProgramTest::new("{program}", {program}::ID, None)
+}}
+"#,
+        program = names.program,
+    )
+}
+
+fn ix_test_rs(fn_name: &str) -> String {
+    format!(
+        r#"#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {{program_test::program_test, solana_program_test::*}};
+
+#[tokio::test]
+async fn {fn_name}_success() {{
+    let _context = program_test().start_with_context().await;
+    todo!()
+}}
+"#
+    )
+}
@@ -0,0 +1,203 @@
+//! `ideally pdas`: generates the `*FindPdaArgs` and `*CreatePdaArgs` types
+//! of the PDAs declared in the IDL's `pdas` section
+
+use std::{collections::HashSet, error::Error, fs, path::PathBuf};
+
+use clap::Args;
+use heck::ToSnakeCase;
+
+use crate::idl::{Idl, IdlPda, IdlSeed};
+
+#[derive(Args, Debug)]
+pub struct PdasArgs {
+    /// Path to the program's handwritten idl.json
+    pub idl: PathBuf,
+
+    /// File to write the generated code to. Defaults to stdout
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Name of the interface crate whose `ID` is the default program ID of PDAs.
+    /// Defaults to `<IDL name>_interface`
+    #[arg(long)]
+    pub interface: Option<String>,
+}
+
+pub fn run(
+    PdasArgs {
+        idl,
+        out,
+        interface,
+    }: PdasArgs,
+) -> Result<(), Box<dyn Error>> {
+    let idl: Idl = serde_json::from_str(&fs::read_to_string(idl)?)?;
+    let interface = interface.unwrap_or_else(|| format!("{}_interface", idl.name.to_snake_case()));
+    let code = pdas_rs(&idl, &interface)?;
+    match out {
+        Some(out) => fs::write(out, code)?,
+        None => print!("{code}"),
+    }
+    Ok(())
+}
+
+/// Generates a module with a `ideally::pda_seeds!` struct and
+/// `ideally::PdaCreateArgs` alias per PDA
+pub fn pdas_rs(idl: &Idl, interface: &str) -> Result<String, Box<dyn Error>> {
+    let accounts: HashSet<&str> = idl
+        .instructions
+        .iter()
+        .flat_map(|ix| ix.accounts.iter().map(|a| a.name.as_str()))
+        .collect();
+    let mut uses_pubkey = false;
+    let mut body = String::new();
+    for pda in idl.pdas.iter() {
+        uses_pubkey |= pda.seeds.iter().any(IdlSeed::is_pubkey);
+        body.push_str(&pda_rs(pda, interface, &accounts)?);
+    }
+    let mut res =
+        String::from("//! Generated from the IDL's `pdas` by `ideally pdas`. Do not edit.\n");
+    if !idl.pdas.is_empty() {
+        res.push_str("\nuse ideally::PdaCreateArgs;\n");
+    }
+    if uses_pubkey {
+        res.push_str("use solana_program::pubkey::Pubkey;\n");
+    }
+    res.push_str(&body);
+    Ok(res)
+}
+
+fn pda_rs(
+    pda: &IdlPda,
+    interface: &str,
+    accounts: &HashSet<&str>,
+) -> Result<String, Box<dyn Error>> {
+    let mut fields = HashSet::new();
+    let mut seeds = String::new();
+    for seed in pda.seeds.iter() {
+        let line = match seed {
+            IdlSeed::Const { value } => format!("{value:?}"),
+            IdlSeed::Account { name } => {
+                if !accounts.contains(name.as_str()) {
+                    return Err(format!(
+                        "PDA {}: seed account {name} is not an account of any instruction",
+                        pda.name
+                    )
+                    .into());
+                }
+                format!("{}: Pubkey", name.to_snake_case())
+            }
+            IdlSeed::Arg { name, ty } => format!("{}: {}", name.to_snake_case(), seed_ty(ty)?),
+        };
+        if let IdlSeed::Account { name } | IdlSeed::Arg { name, .. } = seed {
+            if !fields.insert(name.to_snake_case()) {
+                return Err(format!("PDA {}: duplicate seed {name}", pda.name).into());
+            }
+        }
+        seeds.push_str(&format!("        {line},\n"));
+    }
+    let program_id = match &pda.program_id {
+        Some(id) => format!("solana_program::pubkey!(\"{id}\")"),
+        None => format!("{interface}::ID"),
+    };
+    Ok(format!(
+        r#"
+ideally::pda_seeds! {{
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct {find}(program_id = {program_id}) {{
+{seeds}    }}
+}}
+
+pub type {create} = PdaCreateArgs<{find}>;
+"#,
+        find = pda.find_args_name(),
+        create = pda.create_args_name(),
+    ))
+}
+
+/// Rust type of an `arg` seed's IDL type
+fn seed_ty(ty: &str) -> Result<&'static str, Box<dyn Error>> {
+    Ok(match ty {
+        "u8" => "u8",
+        "u16" => "u16",
+        "u32" => "u32",
+        "u64" => "u64",
+        "u128" => "u128",
+        "publicKey" => "Pubkey",
+        _ => return Err(format!("unsupported seed type {ty}").into()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idl(pdas: &str) -> Idl {
+        serde_json::from_str(&format!(
+            r#"{{
+                "name": "test",
+                "instructions": [
+                    {{ "name": "create", "accounts": [{{ "name": "wallet" }}] }}
+                ],
+                "pdas": {pdas}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_example_generated() {
+        let idl: Idl = serde_json::from_str(include_str!(
+            "../../examples/associated-token-account/idl.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            pdas_rs(&idl, "spl_associated_token_account_interface").unwrap(),
+            include_str!(
+                "../../examples/associated-token-account/spl_associated_token_account_lib/src/pda/generated.rs"
+            )
+        );
+    }
+
+    #[test]
+    fn no_pubkey_import_without_pubkey_seeds() {
+        let code = pdas_rs(
+            &idl(r#"[{
+                    "name": "counter",
+                    "seeds": [
+                        { "kind": "const", "value": "counter" },
+                        { "kind": "arg", "name": "index", "type": "u64" }
+                    ]
+                }]"#),
+            "test_interface",
+        )
+        .unwrap();
+        assert!(code.contains("use ideally::PdaCreateArgs;"));
+        assert!(!code.contains("Pubkey"));
+
+        let code = pdas_rs(&idl("[]"), "test_interface").unwrap();
+        assert!(!code.contains("use "));
+    }
+
+    #[test]
+    fn pubkey_import_with_pubkey_arg_seed() {
+        let code = pdas_rs(
+            &idl(r#"[{ "name": "vault", "seeds": [{ "kind": "arg", "name": "authority", "type": "publicKey" }] }]"#),
+            "test_interface",
+        )
+        .unwrap();
+        assert!(code.contains("use solana_program::pubkey::Pubkey;"));
+    }
+
+    #[test]
+    fn unknown_seed_account_fails() {
+        let err = pdas_rs(
+            &idl(r#"[{ "name": "vault", "seeds": [{ "kind": "account", "name": "walet" }] }]"#),
+            "test_interface",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "PDA vault: seed account walet is not an account of any instruction"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Runs `ideally new` on the example's IDL, with a fake solores that
+//! only writes the files of the interface crate that `ideally new` extends
+#![cfg(unix)]
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+const FAKE_SOLORES: &str = r#"#!/bin/sh
+# -o <out> -n <name> <idl>
+dir="$2/$4"
+mkdir -p "$dir/src"
+printf '[package]\nname = "%s"\nversion = "0.1.0"\nedition = "2021"\n\n[dependencies]\nsolana-program = "^1.16"\n' "$4" > "$dir/Cargo.toml"
+printf 'solana_program::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");\n' > "$dir/src/lib.rs"
+"#;
+
+fn example_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/associated-token-account")
+}
+
+/// Fresh empty directory for the test to work in
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ideally-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn files(dir: &Path, prefix: &Path, res: &mut BTreeSet<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let rel = prefix.join(path.file_name().unwrap());
+        if path.is_dir() {
+            files(&path, &rel, res);
+        } else {
+            res.insert(rel.to_str().unwrap().to_owned());
+        }
+    }
+}
+
+#[test]
+fn new_generates_workspace() {
+    let dir = test_dir("new");
+    let solores = dir.join("solores");
+    fs::write(&solores, FAKE_SOLORES).unwrap();
+    fs::set_permissions(&solores, fs::Permissions::from_mode(0o755)).unwrap();
+    let out = dir.join("out");
+    let ideally_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../ideally");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_ideally"))
+        .arg("new")
+        .arg(example_dir().join("idl.json"))
+        .arg("-o")
+        .arg(&out)
+        .arg("--ideally-path")
+        .arg(&ideally_path)
+        .arg("--solores")
+        .arg(&solores)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let mut generated = BTreeSet::new();
+    files(&out, Path::new(""), &mut generated);
+    let expected: BTreeSet<String> = [
+        "Cargo.toml",
+        "README.md",
+        "idl.json",
+        "program-test/Cargo.toml",
+        "program-test/src/lib.rs",
+        "program-test/tests/program_test.rs",
+        "program-test/tests/create.rs",
+        "program-test/tests/create_idempotent.rs",
+        "program-test/tests/recover_nested.rs",
+        "program-test/tests/create_with_bump.rs",
+        "spl_associated_token_account/Cargo.toml",
+        "spl_associated_token_account/Xargo.toml",
+        "spl_associated_token_account/src/lib.rs",
+        "spl_associated_token_account/src/processor.rs",
+        "spl_associated_token_account_interface/Cargo.toml",
+        "spl_associated_token_account_interface/src/lib.rs",
+        "spl_associated_token_account_interface/src/verify.rs",
+        "spl_associated_token_account_lib/Cargo.toml",
+        "spl_associated_token_account_lib/src/lib.rs",
+        "spl_associated_token_account_lib/src/pda.rs",
+        "spl_associated_token_account_lib/src/resolvers/mod.rs",
+        "spl_associated_token_account_lib/src/resolvers/create.rs",
+        "spl_associated_token_account_lib/src/resolvers/create_idempotent.rs",
+        "spl_associated_token_account_lib/src/resolvers/recover_nested.rs",
+        "spl_associated_token_account_lib/src/resolvers/create_with_bump.rs",
+    ]
+    .map(str::to_owned)
+    .into();
+    assert_eq!(generated, expected);
+
+    let workspace = fs::read_to_string(out.join("Cargo.toml")).unwrap();
+    let patch = format!(
+        "[patch.crates-io]\nideally = {{ path = \"{}\" }}\n",
+        fs::canonicalize(&ideally_path).unwrap().display()
+    );
+    assert!(workspace.ends_with(&patch), "{workspace}");
+
+    let interface_cargo_toml =
+        fs::read_to_string(out.join("spl_associated_token_account_interface/Cargo.toml")).unwrap();
+    assert!(interface_cargo_toml.contains("[dependencies]\nideally = { version = \"^0.1\" }\n"));
+    assert_eq!(
+        fs::read_to_string(out.join("spl_associated_token_account_interface/src/verify.rs"))
+            .unwrap(),
+        fs::read_to_string(
+            example_dir().join("spl_associated_token_account_interface/src/verify.rs")
+        )
+        .unwrap()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn new_fails_if_solores_fails() {
+    let dir = test_dir("solores-fails");
+    let out = dir.join("out");
+    let status = Command::new(env!("CARGO_BIN_EXE_ideally"))
+        .arg("new")
+        .arg(example_dir().join("idl.json"))
+        .arg("-o")
+        .arg(&out)
+        .arg("--solores")
+        .arg("false")
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(!status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,103 @@
+//! Derive macros for the `ideally` crate.
+//!
+//! Use these through `ideally` with the `derive` feature enabled instead of
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod resolver;
+mod static_pda;
+
+/// Derives `ideally::Resolve` for a root accounts struct.
+///
+/// Every field of the struct is a free account. Fields are `Pubkey`s by default.
+/// Annotate fields that are accounts instead with `#[resolver(account)]`; their
+/// key is then obtained with `ideally::solana_readonly_account::KeyedAccount`.
+/// Give each account field its own type parameter so that callers can mix
+/// account sources, e.g. an `AccountInfo` and a cached off-chain snapshot.
+///
+/// The constrained accounts are declared with struct-level attributes,
+/// and are resolved in declaration order:
+///
+/// - `#[resolver(keys = CreateKeys)]`: required, the solores-generated `*Keys` struct to output.
+///   Every field of the `*Keys` struct must be either a field of this struct or a declared constrained account.
+/// - `#[resolver(constant(system_program = system_program::ID))]`: a constant, well-known account
+/// - `#[resolver(owner(token_program = mint))]`: the program owner of the `mint` account field,
+///   obtained with `ideally::solana_readonly_account::ReadonlyAccountOwner`
+/// - `#[resolver(pda(associated_token_account = AtaFindPdaArgs { wallet, token_program, mint }))]`:
+///   a PDA found with the given struct expression, which must implement `ideally::FindPda`.
+///   The expression can refer to any free or previously resolved account by name.
+///   Add `signer` e.g. `pda(owner_ata = AtaFindPdaArgs { .. }, signer)` to output the PDA's
+///   `FindPda::CreateArgs` as `Resolve::Data`. If there are multiple signer PDAs,
+///   `Resolve::Data` is a tuple of their `CreateArgs` in declaration order.
+///
+/// The derived `Resolve::Err` is `core::convert::Infallible`.
+///
+/// `ideally::ResolveWithProgramId` is derived too, finding every PDA with
+/// `FindPda::find_pda_with_program_id` instead.
+///
+/// `ideally::ResolvePinned` and `ideally::ResolvePinnedWithProgramId` are derived too,
+/// so the `*Keys` struct must implement `ideally::KeyFields`. Pinned `owner` and
+/// non-signer `pda` accounts are used as-is instead of being resolved.
+///
+/// Example:
+///
+/// ```rust ignore
+/// #[derive(Resolver)]
+/// #[resolver(keys = CreateKeys)]
+/// #[resolver(owner(token_program = mint))]
+/// #[resolver(constant(system_program = system_program::ID))]
+/// #[resolver(pda(
+///     associated_token_account = AtaFindPdaArgs { wallet, token_program, mint },
+///     signer
+/// ))]
+/// pub struct CreateRootAccounts<M: KeyedAccount + ReadonlyAccountOwner> {
+///     pub funding_account: Pubkey,
+///     pub wallet: Pubkey,
+///     #[resolver(account)]
+///     pub mint: M,
+/// }
+/// ```
+#[proc_macro_derive(Resolver, attributes(resolver))]
+pub fn derive_resolver(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    resolver::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives PDAs with only constant seeds, such as config accounts or global authorities,
+/// at compile time so that programs do not have to `find_program_address()` at runtime.
+///
+/// `program_id` must be a base58 string literal and seeds must be string or byte string literals.
+/// For each PDA `NAME`, generates:
+///
+/// - `const NAME: Pubkey`: the PDA
+/// - `const NAME_BUMP: u8`: its canonical bump
+/// - `const NAME_SIGNER_SEEDS: &[&[u8]]`: its seeds including the bump, for `invoke_signed()`
+///
+/// Example:
+///
+/// ```rust ignore
+/// ideally::static_pda! {
+///     /// The program's global config account
+///     pub CONFIG(program_id = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL") = [b"config"];
+/// }
+///
+/// #[derive(Resolver)]
+/// #[resolver(keys = SetConfigKeys)]
+/// #[resolver(constant(config = CONFIG))]
+/// pub struct SetConfigRootAccounts {
+///     pub admin: Pubkey,
+/// }
+///
+/// invoke_signed(&ix, accounts, &[CONFIG_SIGNER_SEEDS])?;
+/// ```
+#[proc_macro]
+pub fn static_pda(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as static_pda::StaticPdas);
+    static_pda::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,305 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    meta::ParseNestedMeta, punctuated::Punctuated, token::Comma, Attribute, Data, DataStruct,
+    DeriveInput, Error, Expr, ExprStruct, Field, Fields, Ident, Result, Type,
+};
+
+const ATTR: &str = "resolver";
+
+enum Constrained {
+    Constant { name: Ident, value: Expr },
+    Owner { name: Ident, of: Ident },
+    Pda(Pda),
+}
+
+struct Pda {
+    name: Ident,
+    args: ExprStruct,
+    is_signer: bool,
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    keys: Option<Type>,
+    constrained: Vec<Constrained>,
+}
+
+fn single_name(meta: &ParseNestedMeta) -> Result<Ident> {
+    meta.path
+        .get_ident()
+        .cloned()
+        .ok_or_else(|| meta.error("expected account name"))
+}
+
+fn parse_pda(meta: &ParseNestedMeta) -> Result<Pda> {
+    let mut name_args = None;
+    let mut is_signer = false;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("signer") {
+            is_signer = true;
+            return Ok(());
+        }
+        let name = single_name(&inner)?;
+        let args: ExprStruct = inner.value()?.parse()?;
+        name_args = Some((name, args));
+        Ok(())
+    })?;
+    let (name, args) =
+        name_args.ok_or_else(|| meta.error("expected `pda(name = FindPdaArgs { .. })`"))?;
+    Ok(Pda {
+        name,
+        args,
+        is_signer,
+    })
+}
+
+fn parse_struct_attrs(attrs: &[Attribute]) -> Result<StructAttrs> {
+    let mut res = StructAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident(ATTR)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("keys") {
+                res.keys = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("constant") {
+                meta.parse_nested_meta(|inner| {
+                    let name = single_name(&inner)?;
+                    let value = inner.value()?.parse()?;
+                    res.constrained.push(Constrained::Constant { name, value });
+                    Ok(())
+                })?;
+            } else if meta.path.is_ident("owner") {
+                meta.parse_nested_meta(|inner| {
+                    let name = single_name(&inner)?;
+                    let of = inner.value()?.parse()?;
+                    res.constrained.push(Constrained::Owner { name, of });
+                    Ok(())
+                })?;
+            } else if meta.path.is_ident("pda") {
+                res.constrained.push(Constrained::Pda(parse_pda(&meta)?));
+            } else {
+                return Err(meta.error("expected one of `keys`, `constant`, `owner`, `pda`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(res)
+}
+
+fn is_account_field(attrs: &[Attribute]) -> Result<bool> {
+    let mut res = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident(ATTR)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("account") {
+                res = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `account`"))
+            }
+        })?;
+    }
+    Ok(res)
+}
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Resolver can only be derived for structs with named fields",
+            ))
+        }
+    };
+    let StructAttrs { keys, constrained } = parse_struct_attrs(&input.attrs)?;
+    let keys = keys.ok_or_else(|| {
+        Error::new_spanned(&input.ident, "missing `#[resolver(keys = ...)]` attribute")
+    })?;
+
+    let find_pda = |args: &ExprStruct| quote! { ::ideally::FindPda::find_pda(#args) };
+    let find_pda_with_program_id = |args: &ExprStruct| {
+        quote! { ::ideally::FindPda::find_pda_with_program_id(#args, ideally_program_id) }
+    };
+    let (stmts, key_names, data_ty, data_expr) =
+        resolve_body(fields, &constrained, &keys, false, find_pda)?;
+    let (stmts_with_program_id, ..) =
+        resolve_body(fields, &constrained, &keys, false, find_pda_with_program_id)?;
+    let (stmts_pinned, ..) = resolve_body(fields, &constrained, &keys, true, find_pda)?;
+    let (stmts_pinned_with_program_id, ..) =
+        resolve_body(fields, &constrained, &keys, true, find_pda_with_program_id)?;
+    let free_names = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().to_string());
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ideally::Resolve for #ident #ty_generics #where_clause {
+            type Keys = #keys;
+
+            type Data = #data_ty;
+
+            type Err = ::core::convert::Infallible;
+
+            fn resolve(&self) -> ::core::result::Result<(#keys, #data_ty), ::core::convert::Infallible> {
+                #(#stmts)*
+                ::core::result::Result::Ok((
+                    #keys {
+                        #(#key_names),*
+                    },
+                    #data_expr,
+                ))
+            }
+        }
+
+        impl #impl_generics ::ideally::ResolveWithProgramId for #ident #ty_generics #where_clause {
+            fn resolve_with_program_id(
+                &self,
+                ideally_program_id: &::ideally::solana_program::pubkey::Pubkey,
+            ) -> ::core::result::Result<(#keys, #data_ty), ::core::convert::Infallible> {
+                #(#stmts_with_program_id)*
+                ::core::result::Result::Ok((
+                    #keys {
+                        #(#key_names),*
+                    },
+                    #data_expr,
+                ))
+            }
+        }
+
+        impl #impl_generics ::ideally::ResolvePinned for #ident #ty_generics #where_clause {
+            const FREE_ACCOUNTS: &'static [&'static str] = &[#(#free_names),*];
+
+            fn resolve_pinned(
+                &self,
+                ideally_pinned: &[(
+                    <#keys as ::ideally::KeyFields>::Field,
+                    ::ideally::solana_program::pubkey::Pubkey,
+                )],
+            ) -> ::core::result::Result<(#keys, #data_ty), ::core::convert::Infallible> {
+                #(#stmts_pinned)*
+                ::core::result::Result::Ok((
+                    #keys {
+                        #(#key_names),*
+                    },
+                    #data_expr,
+                ))
+            }
+        }
+
+        impl #impl_generics ::ideally::ResolvePinnedWithProgramId for #ident #ty_generics #where_clause {
+            fn resolve_pinned_with_program_id(
+                &self,
+                ideally_pinned: &[(
+                    <#keys as ::ideally::KeyFields>::Field,
+                    ::ideally::solana_program::pubkey::Pubkey,
+                )],
+                ideally_program_id: &::ideally::solana_program::pubkey::Pubkey,
+            ) -> ::core::result::Result<(#keys, #data_ty), ::core::convert::Infallible> {
+                #(#stmts_pinned_with_program_id)*
+                ::core::result::Result::Ok((
+                    #keys {
+                        #(#key_names),*
+                    },
+                    #data_expr,
+                ))
+            }
+        }
+    })
+}
+
+/// Returns the statements binding every key, the key names,
+/// and the type and expression of [`Resolve::Data`].
+///
+/// `find_pda` generates the expression to find a PDA with from its args.
+/// If `pinned`, owner and non-signer PDA accounts use the key pinned
+/// in `ideally_pinned`, if any, instead of resolving it
+fn resolve_body(
+    fields: &Punctuated<Field, Comma>,
+    constrained: &[Constrained],
+    keys: &Type,
+    pinned: bool,
+    find_pda: impl Fn(&ExprStruct) -> TokenStream,
+) -> Result<(Vec<TokenStream>, Vec<Ident>, TokenStream, TokenStream)> {
+    let or_pinned = |name: &Ident, resolve: TokenStream| {
+        if pinned {
+            let name_str = name.to_string();
+            quote! {
+                match ::ideally::pinned_key::<#keys>(ideally_pinned, #name_str) {
+                    ::core::option::Option::Some(pinned) => pinned,
+                    ::core::option::Option::None => #resolve,
+                }
+            }
+        } else {
+            resolve
+        }
+    };
+    let mut key_names = Vec::new();
+    let mut stmts = Vec::new();
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+        stmts.push(if is_account_field(&field.attrs)? {
+            quote! {
+                let #name = *::ideally::solana_readonly_account::KeyedAccount::key(&self.#name);
+            }
+        } else {
+            quote! {
+                let #name = self.#name;
+            }
+        });
+        key_names.push(name.clone());
+    }
+
+    let mut data_tys = Vec::new();
+    let mut data_exprs = Vec::new();
+    for c in constrained {
+        match c {
+            Constrained::Constant { name, value } => {
+                stmts.push(quote! {
+                    let #name = #value;
+                });
+                key_names.push(name.clone());
+            }
+            Constrained::Owner { name, of } => {
+                let resolve = or_pinned(
+                    name,
+                    quote! { *::ideally::solana_readonly_account::ReadonlyAccountOwner::owner(&self.#of) },
+                );
+                stmts.push(quote! {
+                    let #name = #resolve;
+                });
+                key_names.push(name.clone());
+            }
+            Constrained::Pda(Pda {
+                name,
+                args,
+                is_signer,
+            }) => {
+                let find = find_pda(args);
+                if *is_signer {
+                    let args_ty = &args.path;
+                    let create_args = format_ident!("{}_create_pda_args", name);
+                    stmts.push(quote! {
+                        let (#name, #create_args) = #find;
+                    });
+                    data_tys.push(quote! { <#args_ty as ::ideally::FindPda>::CreateArgs });
+                    data_exprs.push(create_args);
+                } else {
+                    let resolve = or_pinned(name, quote! { #find.0 });
+                    stmts.push(quote! {
+                        let #name = #resolve;
+                    });
+                }
+                key_names.push(name.clone());
+            }
+        }
+    }
+
+    let (data_ty, data_expr) = match (data_tys.as_slice(), data_exprs.as_slice()) {
+        ([ty], [expr]) => (ty.clone(), quote! { #expr }),
+        _ => (quote! { (#(#data_tys),*) }, quote! { (#(#data_exprs),*) }),
+    };
+    Ok((stmts, key_names, data_ty, data_expr))
+}
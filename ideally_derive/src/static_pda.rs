@@ -0,0 +1,150 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use sha2::{Digest, Sha256};
+use syn::{
+    bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Ident, Lit, LitByteStr, LitStr, Token, Visibility,
+};
+
+/// `$(#[$attr])* $vis $NAME(program_id = "<base58>") = [$($seed),*];`
+struct StaticPda {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    program_id: LitStr,
+    seeds: Vec<LitByteStr>,
+}
+
+pub struct StaticPdas(Vec<StaticPda>);
+
+impl Parse for StaticPdas {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut res = Vec::new();
+        while !input.is_empty() {
+            res.push(input.parse()?);
+        }
+        Ok(Self(res))
+    }
+}
+
+impl Parse for StaticPda {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let name = input.parse()?;
+
+        let args;
+        parenthesized!(args in input);
+        let key: Ident = args.parse()?;
+        if key != "program_id" {
+            return Err(syn::Error::new(key.span(), "expected `program_id`"));
+        }
+        args.parse::<Token![=]>()?;
+        let program_id = args.parse()?;
+
+        input.parse::<Token![=]>()?;
+        let seeds_content;
+        bracketed!(seeds_content in input);
+        let seeds = Punctuated::<Lit, Token![,]>::parse_terminated(&seeds_content)?
+            .into_iter()
+            .map(|lit| match lit {
+                Lit::ByteStr(b) => Ok(b),
+                Lit::Str(s) => Ok(LitByteStr::new(s.value().as_bytes(), s.span())),
+                lit => Err(syn::Error::new(
+                    lit.span(),
+                    "seeds must be string or byte string literals",
+                )),
+            })
+            .collect::<syn::Result<_>>()?;
+        input.parse::<Token![;]>()?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            name,
+            program_id,
+            seeds,
+        })
+    }
+}
+
+pub fn expand(StaticPdas(pdas): StaticPdas) -> syn::Result<TokenStream> {
+    pdas.iter().map(expand_one).collect()
+}
+
+fn expand_one(
+    StaticPda {
+        attrs,
+        vis,
+        name,
+        program_id,
+        seeds,
+    }: &StaticPda,
+) -> syn::Result<TokenStream> {
+    let program_id_key: [u8; 32] = bs58::decode(program_id.value())
+        .into_vec()
+        .ok()
+        .and_then(|v| v.try_into().ok())
+        .ok_or_else(|| syn::Error::new(program_id.span(), "invalid program_id"))?;
+    let seed_values: Vec<Vec<u8>> = seeds.iter().map(LitByteStr::value).collect();
+    let (address, bump) = try_find_program_address(&seed_values, &program_id_key)
+        .ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "no PDA found for seeds, check that there are at most 15 seeds of at most 32 bytes each",
+            )
+        })?;
+
+    let address_bytes = address;
+    let bump_name = format_ident!("{}_BUMP", name);
+    let signer_seeds_name = format_ident!("{}_SIGNER_SEEDS", name);
+    let address_doc = format!(
+        " `{}`, derived at compile time",
+        bs58::encode(address).into_string()
+    );
+    Ok(quote! {
+        #(#attrs)*
+        #[doc = ""]
+        #[doc = #address_doc]
+        #vis const #name: ::ideally::solana_program::pubkey::Pubkey =
+            ::ideally::solana_program::pubkey::Pubkey::new_from_array([#(#address_bytes),*]);
+
+        #[doc = concat!("Canonical bump of [`", stringify!(#name), "`]")]
+        #vis const #bump_name: u8 = #bump;
+
+        #[doc = concat!("Seeds, including the bump, to `invoke_signed()` for [`", stringify!(#name), "`] with")]
+        #vis const #signer_seeds_name: &[&[u8]] = &[#(#seeds,)* &[#bump]];
+    })
+}
+
+const MAX_SEEDS: usize = 16;
+
+const MAX_SEED_LEN: usize = 32;
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Same as `solana_program::pubkey::Pubkey::try_find_program_address()`,
+/// so that this crate does not need to depend on solana-program
+fn try_find_program_address(seeds: &[Vec<u8>], program_id: &[u8; 32]) -> Option<([u8; 32], u8)> {
+    // bump seed takes up 1
+    if seeds.len() >= MAX_SEEDS || seeds.iter().any(|s| s.len() > MAX_SEED_LEN) {
+        return None;
+    }
+    (1..=u8::MAX).rev().find_map(|bump| {
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update([bump]);
+        hasher.update(program_id);
+        hasher.update(PDA_MARKER);
+        let address: [u8; 32] = hasher.finalize().into();
+        CompressedEdwardsY(address)
+            .decompress()
+            .is_none()
+            .then_some((address, bump))
+    })
+}
@@ -0,0 +1,6 @@
+#[test]
+fn resolver() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/resolver/pass_*.rs");
+    t.compile_fail("tests/ui/resolver/fail_*.rs");
+}
@@ -0,0 +1,59 @@
+use ideally::solana_program::pubkey::Pubkey;
+
+ideally::static_pda! {
+    CONFIG(program_id = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL") = [b"config"];
+
+    pub(crate) VAULT(program_id = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA") = ["vault", b"\x01\x02"];
+
+    NO_SEEDS(program_id = "11111111111111111111111111111111") = [];
+}
+
+fn assert_matches_runtime(
+    address: Pubkey,
+    bump: u8,
+    signer_seeds: &[&[u8]],
+    seeds: &[&[u8]],
+    program_id: &str,
+) {
+    let program_id: Pubkey = program_id.parse().unwrap();
+    assert_eq!(
+        (address, bump),
+        Pubkey::find_program_address(seeds, &program_id)
+    );
+    let bump_seed = [bump];
+    let expected_signer_seeds: Vec<&[u8]> = seeds
+        .iter()
+        .copied()
+        .chain(core::iter::once(bump_seed.as_slice()))
+        .collect();
+    assert_eq!(signer_seeds, expected_signer_seeds.as_slice());
+    assert_eq!(
+        Pubkey::create_program_address(signer_seeds, &program_id).unwrap(),
+        address
+    );
+}
+
+#[test]
+fn matches_find_program_address() {
+    assert_matches_runtime(
+        CONFIG,
+        CONFIG_BUMP,
+        CONFIG_SIGNER_SEEDS,
+        &[b"config"],
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    );
+    assert_matches_runtime(
+        VAULT,
+        VAULT_BUMP,
+        VAULT_SIGNER_SEEDS,
+        &[b"vault", &[1, 2]],
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    );
+    assert_matches_runtime(
+        NO_SEEDS,
+        NO_SEEDS_BUMP,
+        NO_SEEDS_SIGNER_SEEDS,
+        &[],
+        "11111111111111111111111111111111",
+    );
+}
@@ -0,0 +1,38 @@
+use ideally::{solana_program::pubkey::Pubkey, KeyFields, Resolver};
+
+pub struct FooKeys {
+    pub free: Pubkey,
+    pub uncovered: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FooAccountField {
+    Free,
+    Uncovered,
+}
+
+impl KeyFields for FooKeys {
+    type Field = FooAccountField;
+
+    fn key(&self, field: FooAccountField) -> Pubkey {
+        match field {
+            FooAccountField::Free => self.free,
+            FooAccountField::Uncovered => self.uncovered,
+        }
+    }
+
+    fn name(field: FooAccountField) -> &'static str {
+        match field {
+            FooAccountField::Free => "free",
+            FooAccountField::Uncovered => "uncovered",
+        }
+    }
+}
+
+#[derive(Resolver)]
+#[resolver(keys = FooKeys)]
+pub struct FooRootAccounts {
+    pub free: Pubkey,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use ideally::{solana_program::pubkey::Pubkey, Resolver};
+
+#[derive(Resolver)]
+pub struct FooRootAccounts {
+    pub free: Pubkey,
+}
+
+fn main() {}
@@ -0,0 +1,120 @@
+use ideally::{
+    solana_program::pubkey::Pubkey,
+    solana_readonly_account::{KeyedAccount, ReadonlyAccountOwner},
+    FindPda, KeyFields, Resolve, ResolveInfallible, Resolver,
+};
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([1; 32]);
+
+const CONSTANT: Pubkey = Pubkey::new_from_array([2; 32]);
+
+pub struct FooKeys {
+    pub free: Pubkey,
+    pub mint: Pubkey,
+    pub constant: Pubkey,
+    pub token_program: Pubkey,
+    pub pda: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FooAccountField {
+    Free,
+    Mint,
+    Constant,
+    TokenProgram,
+    Pda,
+}
+
+impl KeyFields for FooKeys {
+    type Field = FooAccountField;
+
+    fn key(&self, field: FooAccountField) -> Pubkey {
+        match field {
+            FooAccountField::Free => self.free,
+            FooAccountField::Mint => self.mint,
+            FooAccountField::Constant => self.constant,
+            FooAccountField::TokenProgram => self.token_program,
+            FooAccountField::Pda => self.pda,
+        }
+    }
+
+    fn name(field: FooAccountField) -> &'static str {
+        match field {
+            FooAccountField::Free => "free",
+            FooAccountField::Mint => "mint",
+            FooAccountField::Constant => "constant",
+            FooAccountField::TokenProgram => "token_program",
+            FooAccountField::Pda => "pda",
+        }
+    }
+}
+
+pub struct FooFindPdaArgs {
+    pub free: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl FindPda for FooFindPdaArgs {
+    type CreateArgs = u8;
+
+    const PROGRAM_ID: Pubkey = PROGRAM_ID;
+
+    fn find_pda_with_program_id(self, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[self.free.as_ref(), self.token_program.as_ref()],
+            program_id,
+        )
+    }
+}
+
+pub struct Mint {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+}
+
+impl KeyedAccount for Mint {
+    fn key(&self) -> &Pubkey {
+        &self.key
+    }
+}
+
+impl ReadonlyAccountOwner for Mint {
+    fn owner(&self) -> &Pubkey {
+        &self.owner
+    }
+}
+
+#[derive(Resolver)]
+#[resolver(keys = FooKeys)]
+#[resolver(constant(constant = CONSTANT))]
+#[resolver(owner(token_program = mint))]
+#[resolver(pda(pda = FooFindPdaArgs { free, token_program }, signer))]
+pub struct FooRootAccounts<M: KeyedAccount + ReadonlyAccountOwner> {
+    pub free: Pubkey,
+    #[resolver(account)]
+    pub mint: M,
+}
+
+fn main() {
+    let mint = Mint {
+        key: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+    };
+    let root = FooRootAccounts {
+        free: Pubkey::new_unique(),
+        mint,
+    };
+    let (keys, bump): (FooKeys, u8) = root.resolve_infallible();
+    let (pda, expected_bump) = FooFindPdaArgs {
+        free: root.free,
+        token_program: root.mint.owner,
+    }
+    .find_pda();
+    assert_eq!(keys.free, root.free);
+    assert_eq!(keys.mint, root.mint.key);
+    assert_eq!(keys.constant, CONSTANT);
+    assert_eq!(keys.token_program, root.mint.owner);
+    assert_eq!(keys.pda, pda);
+    assert_eq!(bump, expected_bump);
+    assert!(root.resolve().is_ok());
+}
@@ -0,0 +1,90 @@
+use ideally::{solana_program::pubkey::Pubkey, FindPda, KeyFields, ResolveInfallible, Resolver};
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([1; 32]);
+
+pub struct FooKeys {
+    pub authority: Pubkey,
+    pub vault: Pubkey,
+    pub config: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FooAccountField {
+    Authority,
+    Vault,
+    Config,
+}
+
+impl KeyFields for FooKeys {
+    type Field = FooAccountField;
+
+    fn key(&self, field: FooAccountField) -> Pubkey {
+        match field {
+            FooAccountField::Authority => self.authority,
+            FooAccountField::Vault => self.vault,
+            FooAccountField::Config => self.config,
+        }
+    }
+
+    fn name(field: FooAccountField) -> &'static str {
+        match field {
+            FooAccountField::Authority => "authority",
+            FooAccountField::Vault => "vault",
+            FooAccountField::Config => "config",
+        }
+    }
+}
+
+pub struct VaultCreatePdaArgs {
+    pub bump: u8,
+}
+
+pub struct VaultFindPdaArgs {
+    pub authority: Pubkey,
+}
+
+impl FindPda for VaultFindPdaArgs {
+    type CreateArgs = VaultCreatePdaArgs;
+
+    const PROGRAM_ID: Pubkey = PROGRAM_ID;
+
+    fn find_pda_with_program_id(self, program_id: &Pubkey) -> (Pubkey, VaultCreatePdaArgs) {
+        let (pda, bump) = Pubkey::find_program_address(&[self.authority.as_ref()], program_id);
+        (pda, VaultCreatePdaArgs { bump })
+    }
+}
+
+pub struct ConfigFindPdaArgs;
+
+impl FindPda for ConfigFindPdaArgs {
+    type CreateArgs = u8;
+
+    const PROGRAM_ID: Pubkey = PROGRAM_ID;
+
+    fn find_pda_with_program_id(self, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"config"], program_id)
+    }
+}
+
+#[derive(Resolver)]
+#[resolver(keys = FooKeys)]
+#[resolver(pda(vault = VaultFindPdaArgs { authority }, signer))]
+#[resolver(pda(config = ConfigFindPdaArgs {}, signer))]
+pub struct FooRootAccounts {
+    pub authority: Pubkey,
+}
+
+fn main() {
+    let root = FooRootAccounts {
+        authority: Pubkey::new_unique(),
+    };
+    // Resolve::Data is a tuple of every signer PDA's CreateArgs in declaration order
+    let (keys, (vault_args, config_bump)): (FooKeys, (VaultCreatePdaArgs, u8)) =
+        root.resolve_infallible();
+    let (vault, vault_bump) = Pubkey::find_program_address(&[root.authority.as_ref()], &PROGRAM_ID);
+    let (config, expected_config_bump) = Pubkey::find_program_address(&[b"config"], &PROGRAM_ID);
+    assert_eq!(keys.vault, vault);
+    assert_eq!(vault_args.bump, vault_bump);
+    assert_eq!(keys.config, config);
+    assert_eq!(config_bump, expected_config_bump);
+}